@@ -6,8 +6,8 @@ use proof_of_sql::base::{
 };
 use std::{cmp::Ordering, env, fs::File, io::BufReader, path::Path, sync::Arc};
 use sxt_proof_of_sql_sdk::{
-    base::{zk_query_models::SxtNetwork, CommitmentScheme, DynOwnedTable},
-    native::SxTClient,
+    base::{zk_query_models::SxtNetwork, CommitmentScheme},
+    native::{DynOwnedTable, SxTClient},
 };
 use url::Url;
 
@@ -103,8 +103,9 @@ fn save_to_file(counts: &IndexMap<String, i64>, file_path: &str) {
 async fn main() {
     env_logger::init();
     dotenv::dotenv().unwrap();
+    // Defaults to HyperKZG, matching the CLI
     let commitment_scheme = match env::var("COMMITMENT_SCHEME")
-        .unwrap_or("dynamic-dory".to_string())
+        .unwrap_or("hyper-kzg".to_string())
         .as_str()
     {
         "dynamic-dory" => CommitmentScheme::DynamicDory,
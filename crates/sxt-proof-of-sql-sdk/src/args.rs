@@ -1,4 +1,7 @@
-use crate::{produce_plan_subcommand::ProducePlanArgs, query_and_verify::QueryAndVerifySdkArgs};
+use crate::{
+    produce_plan_subcommand::ProducePlanArgs, query_and_verify::QueryAndVerifySdkArgs,
+    verify_from_file_subcommand::VerifyFromFileArgs,
+};
 use clap::{Parser, Subcommand};
 
 /// Struct to define and parse command-line arguments for Proof of SQL Client.
@@ -21,4 +24,41 @@ pub struct ProofOfSqlSdkArgs {
 pub enum ProofOfSqlSdkSubcommands {
     QueryAndVerify(Box<QueryAndVerifySdkArgs>),
     ProducePlan(Box<ProducePlanArgs>),
+    VerifyFromFile(Box<VerifyFromFileArgs>),
+}
+
+#[cfg(test)]
+#[cfg(feature = "hyperkzg")]
+mod tests {
+    use super::*;
+    use crate::base::CommitmentScheme;
+
+    fn parse(args: &[&str]) -> ProofOfSqlSdkSubcommands {
+        ProofOfSqlSdkArgs::try_parse_from(
+            std::iter::once("proof-of-sql-cli").chain(args.iter().copied()),
+        )
+        .unwrap()
+        .command
+    }
+
+    #[test]
+    fn subcommands_default_to_the_hyper_kzg_commitment_scheme() {
+        let ProofOfSqlSdkSubcommands::QueryAndVerify(args) = parse(&[
+            "query-and-verify",
+            "--sxt-api-key",
+            "key",
+            "--query",
+            "SELECT * FROM ethereum.blocks",
+        ]) else {
+            panic!("expected the query-and-verify subcommand");
+        };
+        assert_eq!(args.commitment_scheme, CommitmentScheme::HyperKzg);
+
+        let ProofOfSqlSdkSubcommands::VerifyFromFile(args) =
+            parse(&["verify-from-file", "--response", "response.json"])
+        else {
+            panic!("expected the verify-from-file subcommand");
+        };
+        assert_eq!(args.commitment_scheme, CommitmentScheme::HyperKzg);
+    }
 }
@@ -1,17 +1,18 @@
 use super::{
     serde::hex::{deserialize_bytes_hex32, serialize_bytes_hex},
-    verifiable_commitment::generate_commitment_leaf,
+    verifiable_commitment::commitment_leaf_hash,
     zk_query_models::TableCommitmentWithProof,
     CommitmentScheme,
 };
 use crate::base::zk_query_models::AttestedCommitments;
-use eth_merkle_tree::utils::{errors::BytesError, keccak::keccak256, verify::verify_proof};
-use indexmap::IndexMap;
-use itertools::{izip, process_results, Itertools};
+use eth_merkle_tree::utils::{errors::BytesError, verify::verify_proof};
+use indexmap::{IndexMap, IndexSet};
+use itertools::{izip, Itertools};
 use k256::ecdsa::{RecoveryId, Signature, VerifyingKey};
 use serde::{Deserialize, Serialize};
 use sha3::{digest::core_api::CoreWrapper, Digest, Keccak256, Keccak256Core};
 use snafu::{ResultExt, Snafu};
+use std::collections::HashSet;
 
 /// Represents an Ethereum-style ECDSA signature, broken into its components.
 ///
@@ -47,6 +48,45 @@ impl EthereumSignature {
     }
 }
 
+/// Serde representation of an [`EthereumSignature`] as a single 65-byte hex string.
+///
+/// The string is the `0x`-prefixed concatenation of `r`, `s` and `v`, as used by most Ethereum
+/// APIs. Can be used in `#[serde(with = "signature_packed")]` attributes for any
+/// `EthereumSignature` field.
+pub mod signature_packed {
+    use super::EthereumSignature;
+    use crate::base::serde::hex::{from_hex, serialize_bytes_hex};
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    /// Serializes a signature as a packed hex string.
+    pub fn serialize<S>(signature: &EthereumSignature, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut packed = [0u8; 65];
+        packed[..32].copy_from_slice(&signature.r);
+        packed[32..64].copy_from_slice(&signature.s);
+        packed[64] = signature.v;
+        serialize_bytes_hex(&packed, serializer)
+    }
+
+    /// Deserializes a signature from a packed hex string, with or without a `0x` prefix.
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<EthereumSignature, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let packed: [u8; 65] = from_hex(&String::deserialize(deserializer)?)
+            .map_err(serde::de::Error::custom)?
+            .try_into()
+            .map_err(|_| serde::de::Error::custom("Invalid length"))?;
+        Ok(EthereumSignature {
+            r: packed[..32].try_into().expect("slice is 32 bytes"),
+            s: packed[32..64].try_into().expect("slice is 32 bytes"),
+            v: packed[64],
+        })
+    }
+}
+
 /// Top-level error type for the attestation module.
 #[derive(Debug, Snafu)]
 pub enum AttestationError {
@@ -68,6 +108,43 @@ pub enum AttestationError {
     /// Attestations do not included every required attestor
     #[snafu(display("At least one required attestor has not signed"))]
     MissingAttestor,
+    /// There are no table commitment attestations to verify the commitments against
+    #[snafu(display("No attestations of the table commitments were provided"))]
+    NoAttestations,
+    /// Fewer distinct signers attested the table commitments than required
+    #[snafu(display("Found attestations from {found} signers, at least {required} are required"))]
+    InsufficientAttestations {
+        /// Number of distinct signers that attested the table commitments
+        found: usize,
+        /// Minimum number of distinct signers required
+        required: usize,
+    },
+    /// An attestation was signed by an address outside the validator allowlist
+    #[snafu(display("Attestor 0x{address} is not an authorized validator"))]
+    UnauthorizedAttestor {
+        /// Hex encoded address of the unauthorized attestor
+        address: String,
+    },
+    /// The `r`, `s` and `v` signature components do not have the same length
+    #[snafu(display(
+        "Mismatched signature components: {r_len} r values, {s_len} s values, {v_len} v values"
+    ))]
+    SignatureLengthMismatch {
+        /// Number of `r` components
+        r_len: usize,
+        /// Number of `s` components
+        s_len: usize,
+        /// Number of `v` components
+        v_len: usize,
+    },
+    /// The attestations are for a different block than the one the query was run at
+    #[snafu(display("Attestations are for block 0x{actual}, expected block 0x{expected}"))]
+    BlockHashMismatch {
+        /// Hex encoded hash of the block the query was run at
+        expected: String,
+        /// Hex encoded hash of the block the attestations are for
+        actual: String,
+    },
 }
 
 /// Specialized `Result` type for the attestation module.
@@ -142,7 +219,7 @@ pub enum SignatureError {
 /// # Errors
 ///
 /// * `VerificationError::SignatureRecoveryError` - If the signature could not be parsed.
-/// * `VerificationError::InvalidRecoveryIdError` - If the recovery ID (`v`) is invalid.
+/// * `VerificationError::InvalidRecoveryIdError` - If the recovery ID (`v`) is not 0-3 or 27/28.
 /// * `VerificationError::KeyRecoveryError` - If the public key cannot be recovered.
 /// * `VerificationError::PublicKeyParsingError` - If the provided public key is invalid.
 /// * `VerificationError::InvalidPublicKeyRecovered` - If the recovered public key does not match the provided key.
@@ -163,12 +240,30 @@ pub fn verify_eth_signature(
     msg: &[u8],
     scalars: &EthereumSignature,
     address20: &[u8],
+) -> Result<()> {
+    verify_eth_signature_with_chain_id(msg, scalars, address20, None)
+}
+
+/// Like [`verify_eth_signature`], but if `chain_id` is set, also accepts EIP-155 signatures whose
+/// `v` is `chain_id * 2 + 35` or `chain_id * 2 + 36`.
+///
+/// Legacy `v` values of 27/28 are accepted either way. Since `v` is a single byte, EIP-155
+/// signatures are only representable for chain ids up to 110.
+pub fn verify_eth_signature_with_chain_id(
+    msg: &[u8],
+    scalars: &EthereumSignature,
+    address20: &[u8],
+    chain_id: Option<u64>,
 ) -> Result<()> {
     let signature = Signature::from_scalars(scalars.r, scalars.s)
         .map_err(|_| AttestationVerificationError::SignatureRecoveryError)
         .context(VerificationSnafu)?;
 
-    let recovery_id = RecoveryId::try_from(scalars.v)
+    let v = match chain_id {
+        Some(chain_id) => normalize_eip155_recovery_id(scalars.v, chain_id),
+        None => normalize_recovery_id(scalars.v),
+    };
+    let recovery_id = RecoveryId::try_from(v)
         .map_err(|_| AttestationVerificationError::InvalidRecoveryIdError {
             recovery_id: scalars.v,
         })
@@ -205,6 +300,27 @@ pub fn verify_eth_signature(
     }
 }
 
+/// Maps Ethereum's conventional `v` values of 27/28 to the raw recovery ids 0/1.
+///
+/// Other values are returned unchanged, so 0-3 pass through and anything else is rejected by
+/// [`RecoveryId::try_from`].
+fn normalize_recovery_id(v: u8) -> u8 {
+    match v {
+        27 | 28 => v - 27,
+        _ => v,
+    }
+}
+
+/// Maps an EIP-155 `v` of `chain_id * 2 + 35` or `chain_id * 2 + 36` to the raw recovery ids 0/1.
+///
+/// Other values are passed to [`normalize_recovery_id`], so legacy 27/28 signatures still verify.
+fn normalize_eip155_recovery_id(v: u8, chain_id: u64) -> u8 {
+    match u64::from(v).checked_sub(chain_id.saturating_mul(2).saturating_add(35)) {
+        Some(recovery_id @ (0 | 1)) => recovery_id as u8,
+        _ => normalize_recovery_id(v),
+    }
+}
+
 /// Hashes a message with the Ethereum-specific prefix.
 ///
 /// # Arguments
@@ -238,6 +354,45 @@ pub fn create_attestation_message<BN: Into<u64>>(
     msg
 }
 
+/// Length of an attestation message: a domain byte, a 32-byte state root and an 8-byte block
+/// number.
+const ATTESTATION_MESSAGE_LEN: usize = 1 + 32 + core::mem::size_of::<u64>();
+
+/// Splits an attestation message back into its state root and block number.
+///
+/// This is the inverse of [`create_attestation_message`] for attested state roots, which include a
+/// leading domain byte: the state root is the first 33 bytes, domain byte included, and the block
+/// number is the trailing 8 big-endian bytes.
+///
+/// Returns `None` unless the message is exactly 41 bytes long.
+pub fn attestation_message_components(msg: &[u8]) -> Option<(&[u8], u64)> {
+    if msg.len() != ATTESTATION_MESSAGE_LEN {
+        return None;
+    }
+    let (state_root, block_number) =
+        msg.split_at(ATTESTATION_MESSAGE_LEN - core::mem::size_of::<u64>());
+    Some((
+        state_root,
+        u64::from_be_bytes(block_number.try_into().ok()?),
+    ))
+}
+
+impl AttestedCommitments {
+    /// Assemble the parallel `r`, `s` and `v` components into signatures.
+    pub fn signatures(&self) -> Result<Vec<EthereumSignature>, AttestationError> {
+        if self.r.len() != self.s.len() || self.r.len() != self.v.len() {
+            return Err(AttestationError::SignatureLengthMismatch {
+                r_len: self.r.len(),
+                s_len: self.s.len(),
+                v_len: self.v.len(),
+            });
+        }
+        Ok(izip!(&self.r, &self.s, &self.v)
+            .map(|(&r, &s, &v)| EthereumSignature { r, s, v })
+            .collect())
+    }
+}
+
 /// Represents attestations stored on-chain.
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Attestation {
@@ -249,16 +404,38 @@ pub struct Attestation {
     state_root: Vec<u8>,
 }
 
+/// The outcome of a successful [`verify_attestations_with_summary`] call.
+#[derive(Debug, Clone)]
+pub struct VerifiedAttestations {
+    /// The commitments whose inclusion was verified.
+    pub commitments: IndexMap<String, TableCommitmentWithProof>,
+    /// The distinct attestors whose attestations were verified.
+    pub attestors: IndexSet<Vec<u8>>,
+    /// The number of duplicate attestations dropped before verification.
+    pub duplicates_dropped: usize,
+}
+
 /// Now verify for each attestation and every commitment
 pub fn verify_attestations(
     attested_commitments: &AttestedCommitments,
     required_attestors: Vec<[u8; 20]>,
     commitment_scheme: CommitmentScheme,
 ) -> Result<IndexMap<String, TableCommitmentWithProof>, AttestationError> {
+    verify_attestations_with_summary(attested_commitments, required_attestors, commitment_scheme)
+        .map(|verified| verified.commitments)
+}
+
+/// Verify for each distinct attestation and every commitment, reporting who attested.
+///
+/// Attestations repeating the same signer and state root are verified and counted once.
+pub fn verify_attestations_with_summary(
+    attested_commitments: &AttestedCommitments,
+    required_attestors: Vec<[u8; 20]>,
+    commitment_scheme: CommitmentScheme,
+) -> Result<VerifiedAttestations, AttestationError> {
+    let signatures = attested_commitments.signatures()?;
     let attestations = [
-        attested_commitments.r.len(),
-        attested_commitments.s.len(),
-        attested_commitments.v.len(),
+        signatures.len(),
         attested_commitments.address20s.len(),
         attested_commitments.state_root.len(),
     ]
@@ -266,14 +443,12 @@ pub fn verify_attestations(
     .all_equal()
     .then(|| {
         izip!(
-            attested_commitments.r.clone(),
-            attested_commitments.s.clone(),
-            attested_commitments.v.clone(),
+            signatures,
             attested_commitments.address20s.clone(),
             attested_commitments.state_root.clone(),
         )
-        .map(|(r, s, v, address20, state_root)| Attestation {
-            signature: EthereumSignature { r, s, v },
+        .map(|(signature, address20, state_root)| Attestation {
+            signature,
             address20,
             state_root,
         })
@@ -281,13 +456,26 @@ pub fn verify_attestations(
     })
     .ok_or(AttestationError::MalformedData)?;
     // Early filtering: extract table commitments attestations
-    let table_commitments_attestations: Vec<_> = attestations
+    let filtered_attestations: Vec<_> = attestations
         .iter()
-        .filter(|Attestation { state_root, .. }| {
-            // Filter out state_roots with length != 33 or first byte != 0x00
-            state_root.len() == 33 && state_root[0] == 0x00
-        })
+        .filter(|Attestation { state_root, .. }| is_table_commitments_state_root(state_root))
         .collect::<Vec<_>>();
+    let num_filtered_attestations = filtered_attestations.len();
+    // Drop repeated attestations from the same signer to the same state root
+    let table_commitments_attestations: Vec<_> = filtered_attestations
+        .into_iter()
+        .unique_by(|attestation| {
+            (
+                attestation.address20.clone(),
+                attestation.state_root.clone(),
+            )
+        })
+        .collect();
+    let duplicates_dropped = num_filtered_attestations - table_commitments_attestations.len();
+    // With no attestations every commitment would trivially pass the checks below
+    if table_commitments_attestations.is_empty() {
+        return Err(AttestationError::NoAttestations);
+    }
 
     if !required_attestors.iter().all(|attestor| {
         table_commitments_attestations
@@ -299,48 +487,129 @@ pub fn verify_attestations(
 
     let block_number = attested_commitments.block_number;
 
-    let is_valid = process_results(
-        table_commitments_attestations
+    table_commitments_attestations
+        .iter()
+        .cartesian_product(attested_commitments.commitments.iter())
+        .try_for_each(
+            |(attestation, (table_id, commitment_with_proof))| -> Result<(), AttestationError> {
+                // We need to verify
+                // 1. The signature on the attestation is valid
+                // 2. The [`TableCommitmentBytes`] is in fact a leaf in the attestation tree and that
+                //    the provided Merkle proof in [`TableCommitmentWithProof`] is valid for the leaf
+                //    with respect to the attestation's state root
+                let Attestation {
+                    state_root,
+                    signature,
+                    address20,
+                    ..
+                } = attestation;
+                let attestation_message = create_attestation_message(state_root, block_number);
+                verify_eth_signature(&attestation_message, signature, address20)?;
+                // Remove the first byte for it is the AttestationDomain
+                Ok(verify_commitment_inclusion(
+                    table_id,
+                    commitment_with_proof,
+                    commitment_scheme,
+                    &state_root[1..],
+                )?)
+            },
+        )?;
+    Ok(VerifiedAttestations {
+        commitments: attested_commitments.commitments.clone(),
+        attestors: table_commitments_attestations
             .iter()
-            .cartesian_product(attested_commitments.commitments.iter())
-            .map(
-                |(attestation, (table_id, commitment_with_proof))| -> Result<bool, AttestationError> {
-                    // We need to verify
-                    // 1. The signature on the attestation is valid
-                    // 2. The [`TableCommitmentBytes`] is in fact a leaf in the attestation tree and that
-                    //    the provided Merkle proof in [`TableCommitmentWithProof`] is valid for the leaf
-                    //    with respect to the attestation's state root
-                    let Attestation {
-                        state_root,
-                        signature,
-                        address20,
-                        ..
-                    } = attestation;
-                    let attestation_message = create_attestation_message(state_root, block_number);
-                    verify_eth_signature(&attestation_message, signature, address20)?;
-                    // Remove the first byte for it is the AttestationDomain
-                    let actual_state_root = &state_root[1..];
-                    let encoded_root = hex::encode(actual_state_root);
-                    let keccak_encoded_leaf = keccak256(&hex::encode(generate_commitment_leaf(
-                        table_id.to_string(),
-                        commitment_scheme,
-                        commitment_with_proof.commitment.clone(),
-                    )))?;
-                    Ok(verify_proof(
-                        commitment_with_proof.merkle_proof.clone(),
-                        &encoded_root,
-                        &keccak_encoded_leaf,
-                    )?)
-                },
-            ),
-        |mut iter| iter.all(|ok| ok),
-    )?;
-    if !is_valid {
-        return Err(AttestationError::VerificationError {
-            source: AttestationVerificationError::FailureToVerifyMerkleProof,
+            .map(|attestation| attestation.address20.clone())
+            .collect(),
+        duplicates_dropped,
+    })
+}
+
+/// Verify that a table commitment is a leaf of the attestation Merkle tree with the given root.
+///
+/// `state_root` is the root of the tree, without the leading attestation domain byte of an
+/// attested state root. This is the Merkle half of [`verify_attestations`], for callers that
+/// check signatures on their own.
+pub fn verify_commitment_inclusion(
+    table_id: &str,
+    commitment: &TableCommitmentWithProof,
+    scheme: CommitmentScheme,
+    state_root: &[u8],
+) -> Result<(), AttestationVerificationError> {
+    let keccak_encoded_leaf =
+        commitment_leaf_hash(table_id.to_string(), scheme, commitment.commitment.clone())
+            .map_err(|err| AttestationVerificationError::BytesError { err })?;
+    let is_included = verify_proof(
+        commitment.merkle_proof.clone(),
+        &hex::encode(state_root),
+        &keccak_encoded_leaf,
+    )
+    .map_err(|err| AttestationVerificationError::BytesError { err })?;
+    if is_included {
+        Ok(())
+    } else {
+        Err(AttestationVerificationError::FailureToVerifyMerkleProof)
+    }
+}
+
+/// Whether a state root attests to table commitments, as opposed to another attestation domain.
+fn is_table_commitments_state_root(state_root: &[u8]) -> bool {
+    // Filter out state_roots with length != 33 or first byte != 0x00
+    state_root.len() == 33 && state_root[0] == 0x00
+}
+
+/// Check that at least `required` distinct signers attested the table commitments.
+///
+/// This only counts attestations; they still need to be verified with [`verify_attestations`].
+pub fn verify_attestation_count(
+    attested_commitments: &AttestedCommitments,
+    required: usize,
+) -> Result<(), AttestationError> {
+    let found = attested_commitments
+        .address20s
+        .iter()
+        .zip(&attested_commitments.state_root)
+        .filter(|(_, state_root)| is_table_commitments_state_root(state_root))
+        .map(|(address20, _)| address20)
+        .unique()
+        .count();
+    if found < required {
+        return Err(AttestationError::InsufficientAttestations { found, required });
+    }
+    Ok(())
+}
+
+/// Verify attestations as [`verify_attestations_with_summary`] does, additionally requiring that
+/// every attestor is in `allowed_addresses`.
+pub fn verify_attestations_against_validators(
+    attested_commitments: &AttestedCommitments,
+    commitment_scheme: CommitmentScheme,
+    allowed_addresses: &HashSet<[u8; 20]>,
+) -> Result<VerifiedAttestations, AttestationError> {
+    let verified =
+        verify_attestations_with_summary(attested_commitments, vec![], commitment_scheme)?;
+    if let Some(unauthorized) = verified.attestors.iter().find(|address20| {
+        <[u8; 20]>::try_from(address20.as_slice())
+            .map_or(true, |address20| !allowed_addresses.contains(&address20))
+    }) {
+        return Err(AttestationError::UnauthorizedAttestor {
+            address: hex::encode(unauthorized),
         });
     }
-    Ok(attested_commitments.commitments.clone())
+    Ok(verified)
+}
+
+/// Check that attested commitments are for the block the query was run at.
+pub fn verify_attested_block(
+    attested_commitments: &AttestedCommitments,
+    expected_block_hash: [u8; 32],
+) -> Result<(), AttestationError> {
+    if attested_commitments.block_hash != expected_block_hash {
+        return Err(AttestationError::BlockHashMismatch {
+            expected: hex::encode(expected_block_hash),
+            actual: hex::encode(attested_commitments.block_hash),
+        });
+    }
+    Ok(())
 }
 
 #[cfg(test)]
@@ -401,6 +670,44 @@ mod tests {
         Ok(EthereumSignature::new(r, s, Some(recovery_id.into())))
     }
 
+    #[test]
+    fn we_can_round_trip_a_signature_through_the_packed_form() {
+        #[derive(Serialize, Deserialize, Debug, PartialEq)]
+        struct Packed(#[serde(with = "signature_packed")] EthereumSignature);
+
+        let signature: EthereumSignature = serde_json::from_value(serde_json::json!({
+            "r": format!("0x{}", "01".repeat(32)),
+            "s": format!("0x{}", "02".repeat(32)),
+            "v": 27,
+        }))
+        .unwrap();
+        let packed = serde_json::to_value(Packed(signature)).unwrap();
+        assert_eq!(
+            packed,
+            serde_json::json!(format!("0x{}{}1b", "01".repeat(32), "02".repeat(32)))
+        );
+        assert_eq!(
+            serde_json::from_value::<Packed>(packed).unwrap(),
+            Packed(signature)
+        );
+
+        // The packed form is also accepted without a prefix, but only at 65 bytes
+        assert_eq!(
+            serde_json::from_value::<Packed>(serde_json::json!(format!(
+                "{}{}1b",
+                "01".repeat(32),
+                "02".repeat(32)
+            )))
+            .unwrap(),
+            Packed(signature)
+        );
+        assert!(serde_json::from_value::<Packed>(serde_json::json!(format!(
+            "0x{}",
+            "01".repeat(64)
+        )))
+        .is_err());
+    }
+
     #[test]
     fn test_ethereum_signature_new_with_v() {
         let r = [1u8; 32];
@@ -485,6 +792,32 @@ mod tests {
         assert_eq!(&message[32..], &(block_number as u64).to_be_bytes()[..]);
     }
 
+    #[test]
+    fn test_attestation_message_components_round_trip() {
+        let mut domain_state_root = vec![0x01];
+        domain_state_root.extend_from_slice(&[0xaau8; 32]);
+        let message = create_attestation_message(&domain_state_root, 12345u64);
+        assert_eq!(
+            attestation_message_components(&message),
+            Some((&domain_state_root[..], 12345))
+        );
+
+        let message = create_attestation_message(&domain_state_root, u64::MAX);
+        assert_eq!(
+            attestation_message_components(&message),
+            Some((&domain_state_root[..], u64::MAX))
+        );
+    }
+
+    #[test]
+    fn test_attestation_message_components_wrong_length() {
+        assert_eq!(attestation_message_components(&[0u8; 7]), None);
+        // A state root without its domain byte
+        assert_eq!(attestation_message_components(&[0u8; 40]), None);
+        assert_eq!(attestation_message_components(&[0u8; 42]), None);
+        assert!(attestation_message_components(&[0u8; 41]).is_some());
+    }
+
     #[test]
     fn test_sign_eth_message_valid_key() {
         // Using a known test private key (DO NOT USE IN PRODUCTION)
@@ -662,6 +995,106 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_normalize_recovery_id() {
+        assert_eq!(normalize_recovery_id(0), 0);
+        assert_eq!(normalize_recovery_id(1), 1);
+        assert_eq!(normalize_recovery_id(27), 0);
+        assert_eq!(normalize_recovery_id(28), 1);
+        assert_eq!(normalize_recovery_id(255), 255);
+    }
+
+    #[test]
+    fn test_verify_eth_signature_with_raw_and_ethereum_recovery_ids() {
+        let private_key = [
+            0x01, 0x23, 0x45, 0x67, 0x89, 0xab, 0xcd, 0xef, 0x01, 0x23, 0x45, 0x67, 0x89, 0xab,
+            0xcd, 0xef, 0x01, 0x23, 0x45, 0x67, 0x89, 0xab, 0xcd, 0xef, 0x01, 0x23, 0x45, 0x67,
+            0x89, 0xab, 0xcd, 0xef,
+        ];
+        let message = b"test message";
+        let signature = sign_eth_message(&private_key, message).unwrap();
+
+        let signing_key = SigningKey::from_bytes(&private_key.into()).unwrap();
+        let address20 = Keccak256::digest(
+            &signing_key
+                .verifying_key()
+                .to_encoded_point(false)
+                .as_bytes()[1..],
+        )[12..]
+            .to_vec();
+
+        // The raw recovery id and its Ethereum form (+27) both verify
+        for v in [signature.v, signature.v + 27] {
+            let signature = EthereumSignature { v, ..signature };
+            assert!(verify_eth_signature(message, &signature, &address20).is_ok());
+        }
+        // The other parity, in either form, recovers a different key
+        for v in [1 - signature.v, 28 - signature.v] {
+            let signature = EthereumSignature { v, ..signature };
+            assert!(verify_eth_signature(message, &signature, &address20).is_err());
+        }
+    }
+
+    #[test]
+    fn test_normalize_eip155_recovery_id() {
+        // Mainnet (chain id 1) uses 37/38
+        assert_eq!(normalize_eip155_recovery_id(37, 1), 0);
+        assert_eq!(normalize_eip155_recovery_id(38, 1), 1);
+        assert_eq!(normalize_eip155_recovery_id(27, 1), 0);
+        assert_eq!(normalize_eip155_recovery_id(28, 1), 1);
+        assert_eq!(normalize_eip155_recovery_id(39, 1), 39);
+        assert_eq!(normalize_eip155_recovery_id(37, u64::MAX), 37);
+    }
+
+    #[test]
+    fn test_verify_eth_signature_with_legacy_and_eip155_recovery_ids() {
+        let private_key = [
+            0x01, 0x23, 0x45, 0x67, 0x89, 0xab, 0xcd, 0xef, 0x01, 0x23, 0x45, 0x67, 0x89, 0xab,
+            0xcd, 0xef, 0x01, 0x23, 0x45, 0x67, 0x89, 0xab, 0xcd, 0xef, 0x01, 0x23, 0x45, 0x67,
+            0x89, 0xab, 0xcd, 0xef,
+        ];
+        let message = b"test message";
+        let signature = sign_eth_message(&private_key, message).unwrap();
+
+        let signing_key = SigningKey::from_bytes(&private_key.into()).unwrap();
+        let address20 = Keccak256::digest(
+            &signing_key
+                .verifying_key()
+                .to_encoded_point(false)
+                .as_bytes()[1..],
+        )[12..]
+            .to_vec();
+
+        let chain_id = 5;
+        let legacy = EthereumSignature {
+            v: signature.v + 27,
+            ..signature
+        };
+        let eip155 = EthereumSignature {
+            v: signature.v + 2 * chain_id as u8 + 35,
+            ..signature
+        };
+
+        // Both forms recover the same key when the chain id is known
+        for signature in [legacy, eip155] {
+            assert!(verify_eth_signature_with_chain_id(
+                message,
+                &signature,
+                &address20,
+                Some(chain_id)
+            )
+            .is_ok());
+        }
+        // Without the chain id, only the legacy form is accepted
+        assert!(verify_eth_signature(message, &legacy, &address20).is_ok());
+        assert!(matches!(
+            verify_eth_signature(message, &eip155, &address20),
+            Err(AttestationError::VerificationError {
+                source: AttestationVerificationError::InvalidRecoveryIdError { .. }
+            })
+        ));
+    }
+
     #[test]
     fn test_sign_and_verify_roundtrip() {
         let private_key = [
@@ -843,7 +1276,63 @@ mod tests {
 
     #[cfg(feature = "hyperkzg")]
     #[test]
-    fn test_verify_attestations_work_if_all_attestations_have_filtered_out_state_roots() {
+    fn test_verify_commitment_inclusion() {
+        let commitment = &TABLE_COMMITMENTS_WITH_PROOF["ETHEREUM.BLOCKS"];
+        let state_root =
+            hex::decode("1c9eacb80783f8e6f9bd2645ec40d91dc294512bb4c53d68cb07f9e056d1904e")
+                .unwrap();
+
+        verify_commitment_inclusion(
+            "ETHEREUM.BLOCKS",
+            commitment,
+            CommitmentScheme::HyperKzg,
+            &state_root,
+        )
+        .unwrap();
+
+        // The leaf commits to the table name and the scheme as well as the commitment
+        for (table_id, scheme, state_root) in [
+            (
+                "ETHEREUM.TRANSACTIONS",
+                CommitmentScheme::HyperKzg,
+                &state_root,
+            ),
+            (
+                "ETHEREUM.BLOCKS",
+                CommitmentScheme::DynamicDory,
+                &state_root,
+            ),
+            ("ETHEREUM.BLOCKS", CommitmentScheme::HyperKzg, &vec![0; 32]),
+        ] {
+            assert!(matches!(
+                verify_commitment_inclusion(table_id, commitment, scheme, state_root),
+                Err(AttestationVerificationError::FailureToVerifyMerkleProof)
+            ));
+        }
+    }
+
+    #[cfg(feature = "hyperkzg")]
+    #[test]
+    fn test_verify_attestations_fail_without_attestations() {
+        let attested_commitments = AttestedCommitments {
+            commitments: TABLE_COMMITMENTS_WITH_PROOF.clone(),
+            r: vec![],
+            s: vec![],
+            v: vec![],
+            state_root: vec![],
+            address20s: vec![],
+            block_number: 1,
+            block_hash: [0; 32],
+        };
+
+        let result = verify_attestations(&attested_commitments, vec![], CommitmentScheme::HyperKzg);
+
+        assert!(matches!(result, Err(AttestationError::NoAttestations)));
+    }
+
+    #[cfg(feature = "hyperkzg")]
+    #[test]
+    fn test_verify_attestations_fail_if_all_attestations_have_filtered_out_state_roots() {
         let attested_commitments = AttestedCommitments {
             commitments: TABLE_COMMITMENTS_WITH_PROOF.clone(),
             r: vec![[0; 32], [0; 32]],
@@ -859,12 +1348,11 @@ mod tests {
             block_hash: [0; 32],
         };
 
-        // Since there are no table commitments attestations after filtering, and we have commitments
-        // to verify, the verification should be verification success due to no table commitments
-        // attestations existing
+        // There are no table commitments attestations left after filtering, so nothing vouches
+        // for the commitments
         let result = verify_attestations(&attested_commitments, vec![], CommitmentScheme::HyperKzg);
 
-        assert!(result.is_ok());
+        assert!(matches!(result, Err(AttestationError::NoAttestations)));
     }
 
     #[cfg(feature = "hyperkzg")]
@@ -946,4 +1434,218 @@ mod tests {
             AttestationError::MissingAttestor
         ));
     }
+
+    #[cfg(feature = "hyperkzg")]
+    #[test]
+    fn test_duplicate_attestations_are_counted_once() {
+        let r: [u8; 32] =
+            hex::decode("840689485acafc5df1324d81d0667c40712c3c3a17fd6abba28ef50c3d4f3945")
+                .unwrap()
+                .try_into()
+                .unwrap();
+        let s: [u8; 32] =
+            hex::decode("14c3fe046eecd3ed14bc4d36bea8a70faa0eafb2ac8794e490166c1414a6c743")
+                .unwrap()
+                .try_into()
+                .unwrap();
+        let state_root =
+            hex::decode("001c9eacb80783f8e6f9bd2645ec40d91dc294512bb4c53d68cb07f9e056d1904e")
+                .unwrap();
+        let address20 = hex::decode("813d6af4222a6b8ea3237f3a9eb7a9d58ade2ace").unwrap();
+        let attested_commitments = AttestedCommitments {
+            commitments: TABLE_COMMITMENTS_WITH_PROOF.clone(),
+            r: vec![r, r],
+            s: vec![s, s],
+            v: vec![1, 1],
+            state_root: vec![state_root.clone(), state_root],
+            address20s: vec![address20.clone(), address20.clone()],
+            block_number: 4539877,
+            block_hash: hex::decode(
+                "631a6cdd6a156d7e61fe7627ab04b7c748e4d61a29f13aee0b54d458fbcc87fe",
+            )
+            .unwrap()
+            .try_into()
+            .unwrap(),
+        };
+
+        let verified = verify_attestations_with_summary(
+            &attested_commitments,
+            vec![],
+            CommitmentScheme::HyperKzg,
+        )
+        .unwrap();
+        assert_eq!(verified.attestors, IndexSet::from([address20]));
+        assert_eq!(verified.duplicates_dropped, 1);
+    }
+
+    #[cfg(feature = "hyperkzg")]
+    fn two_valid_attestations() -> AttestedCommitments {
+        AttestedCommitments {
+            commitments: TABLE_COMMITMENTS_WITH_PROOF.clone(),
+            r: vec![
+                hex::decode("840689485acafc5df1324d81d0667c40712c3c3a17fd6abba28ef50c3d4f3945")
+                    .unwrap()
+                    .try_into()
+                    .unwrap(),
+                hex::decode("4db877e787216abef007c5fdc4332b44dfba84ef2b05146addba4320d372b24c")
+                    .unwrap()
+                    .try_into()
+                    .unwrap(),
+            ],
+            s: vec![
+                hex::decode("14c3fe046eecd3ed14bc4d36bea8a70faa0eafb2ac8794e490166c1414a6c743")
+                    .unwrap()
+                    .try_into()
+                    .unwrap(),
+                hex::decode("0cf12eadf29cd4fd8c897ab570303e007e7532234c89e04a49ab79b1b3eb85f8")
+                    .unwrap()
+                    .try_into()
+                    .unwrap(),
+            ],
+            v: vec![1, 1],
+            state_root: vec![
+                hex::decode("001c9eacb80783f8e6f9bd2645ec40d91dc294512bb4c53d68cb07f9e056d1904e")
+                    .unwrap(),
+                hex::decode("001c9eacb80783f8e6f9bd2645ec40d91dc294512bb4c53d68cb07f9e056d1904e")
+                    .unwrap(),
+            ],
+            address20s: vec![
+                hex::decode("813d6af4222a6b8ea3237f3a9eb7a9d58ade2ace").unwrap(),
+                hex::decode("8c2b9f40a674ca91f8ac5ff30eb17b80d768f209").unwrap(),
+            ],
+            block_number: 4539877,
+            block_hash: hex::decode(
+                "631a6cdd6a156d7e61fe7627ab04b7c748e4d61a29f13aee0b54d458fbcc87fe",
+            )
+            .unwrap()
+            .try_into()
+            .unwrap(),
+        }
+    }
+
+    #[cfg(feature = "hyperkzg")]
+    #[test]
+    fn test_verify_attestations_against_authorized_validators() {
+        let allowed_addresses: HashSet<[u8; 20]> = [
+            "813d6af4222a6b8ea3237f3a9eb7a9d58ade2ace",
+            "8c2b9f40a674ca91f8ac5ff30eb17b80d768f209",
+            "e7c9f4d5b48920f6e561b4889bb9bef9874c57e0",
+        ]
+        .iter()
+        .map(|address| hex::decode(address).unwrap().try_into().unwrap())
+        .collect();
+
+        let verified = verify_attestations_against_validators(
+            &two_valid_attestations(),
+            CommitmentScheme::HyperKzg,
+            &allowed_addresses,
+        )
+        .unwrap();
+        assert_eq!(verified.attestors.len(), 2);
+    }
+
+    #[cfg(feature = "hyperkzg")]
+    #[test]
+    fn test_verify_attestations_rejects_unknown_validator() {
+        let allowed_addresses: HashSet<[u8; 20]> =
+            HashSet::from([hex::decode("813d6af4222a6b8ea3237f3a9eb7a9d58ade2ace")
+                .unwrap()
+                .try_into()
+                .unwrap()]);
+
+        let result = verify_attestations_against_validators(
+            &two_valid_attestations(),
+            CommitmentScheme::HyperKzg,
+            &allowed_addresses,
+        );
+        assert!(matches!(
+            result.unwrap_err(),
+            AttestationError::UnauthorizedAttestor { address }
+                if address == "8c2b9f40a674ca91f8ac5ff30eb17b80d768f209"
+        ));
+    }
+
+    #[cfg(feature = "hyperkzg")]
+    #[test]
+    fn test_verify_attested_block() {
+        let attested_commitments = two_valid_attestations();
+        assert!(
+            verify_attested_block(&attested_commitments, attested_commitments.block_hash).is_ok()
+        );
+
+        let result = verify_attested_block(&attested_commitments, [0; 32]);
+        assert!(matches!(
+            result.unwrap_err(),
+            AttestationError::BlockHashMismatch { expected, actual }
+                if expected == hex::encode([0; 32])
+                    && actual == "631a6cdd6a156d7e61fe7627ab04b7c748e4d61a29f13aee0b54d458fbcc87fe"
+        ));
+    }
+
+    #[cfg(feature = "hyperkzg")]
+    #[test]
+    fn test_verify_attestation_count() {
+        let mut attested_commitments = two_valid_attestations();
+        assert!(verify_attestation_count(&attested_commitments, 2).is_ok());
+        assert!(matches!(
+            verify_attestation_count(&attested_commitments, 3),
+            Err(AttestationError::InsufficientAttestations {
+                found: 2,
+                required: 3
+            })
+        ));
+
+        // A repeated signer only counts once
+        attested_commitments.address20s[1] = attested_commitments.address20s[0].clone();
+        assert!(matches!(
+            verify_attestation_count(&attested_commitments, 2),
+            Err(AttestationError::InsufficientAttestations {
+                found: 1,
+                required: 2
+            })
+        ));
+    }
+
+    #[cfg(feature = "hyperkzg")]
+    #[test]
+    fn test_signatures_from_aligned_components() {
+        let attested_commitments = two_valid_attestations();
+        let signatures = attested_commitments.signatures().unwrap();
+        assert_eq!(
+            signatures,
+            vec![
+                EthereumSignature {
+                    r: attested_commitments.r[0],
+                    s: attested_commitments.s[0],
+                    v: 1,
+                },
+                EthereumSignature {
+                    r: attested_commitments.r[1],
+                    s: attested_commitments.s[1],
+                    v: 1,
+                },
+            ]
+        );
+    }
+
+    #[cfg(feature = "hyperkzg")]
+    #[test]
+    fn test_signatures_from_mismatched_components() {
+        let attested_commitments = AttestedCommitments {
+            v: vec![1],
+            ..two_valid_attestations()
+        };
+        assert!(matches!(
+            attested_commitments.signatures(),
+            Err(AttestationError::SignatureLengthMismatch {
+                r_len: 2,
+                s_len: 2,
+                v_len: 1
+            })
+        ));
+        assert!(matches!(
+            verify_attestations(&attested_commitments, vec![], CommitmentScheme::HyperKzg),
+            Err(AttestationError::SignatureLengthMismatch { .. })
+        ));
+    }
 }
@@ -7,9 +7,10 @@ use proof_of_sql::base::commitment::CommitmentEvaluationProof;
 #[cfg(all(feature = "hyperkzg", feature = "native"))]
 use proof_of_sql::proof_primitive::hyperkzg::HyperKZGEngine;
 use serde::{Deserialize, Serialize};
+use snafu::Snafu;
 
 /// Commitment schemes used in the proof-of-sql SDK.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[cfg_attr(feature = "native", derive(clap::ValueEnum))]
 #[repr(u8)]
 pub enum CommitmentScheme {
@@ -30,6 +31,31 @@ impl core::fmt::Display for CommitmentScheme {
     }
 }
 
+/// Error returned when parsing a [`CommitmentScheme`] from an unrecognized name.
+#[derive(Snafu, Debug, PartialEq, Eq)]
+#[snafu(display("unknown commitment scheme: {scheme}"))]
+pub struct UnknownCommitmentSchemeError {
+    /// The name that could not be parsed
+    scheme: String,
+}
+
+/// Parses a scheme from its display name (e.g. `HyperKzg`) or its ZK Query API name (e.g.
+/// `HYPER_KZG`), ignoring case.
+impl core::str::FromStr for CommitmentScheme {
+    type Err = UnknownCommitmentSchemeError;
+
+    fn from_str(scheme: &str) -> Result<Self, Self::Err> {
+        match scheme.replace(['_', '-'], "").to_ascii_lowercase().as_str() {
+            "dynamicdory" => Ok(CommitmentScheme::DynamicDory),
+            #[cfg(feature = "hyperkzg")]
+            "hyperkzg" => Ok(CommitmentScheme::HyperKzg),
+            _ => Err(UnknownCommitmentSchemeError {
+                scheme: scheme.to_string(),
+            }),
+        }
+    }
+}
+
 #[cfg(feature = "hyperkzg")]
 pub(crate) const HYPER_KZG_VERIFIER_SETUP_BYTES: &[u8] = include_bytes!(concat!(
     env!("CARGO_MANIFEST_DIR"),
@@ -47,6 +73,59 @@ impl From<CommitmentScheme> for prover::CommitmentScheme {
     }
 }
 
+/// Error returned when converting a `prover::CommitmentScheme` that this SDK cannot verify.
+#[derive(Snafu, Debug, PartialEq, Eq)]
+#[snafu(display("commitment scheme {scheme:?} is not supported by this SDK"))]
+pub struct UnsupportedCommitmentSchemeError {
+    /// The scheme that has no [`CommitmentScheme`] equivalent
+    scheme: prover::CommitmentScheme,
+}
+
+/// Convert a `prover::CommitmentScheme` to a `CommitmentScheme`.
+///
+/// IPA has no equivalent, nor has Hyper KZG without the `hyperkzg` feature.
+impl TryFrom<prover::CommitmentScheme> for CommitmentScheme {
+    type Error = UnsupportedCommitmentSchemeError;
+
+    fn try_from(scheme: prover::CommitmentScheme) -> Result<Self, Self::Error> {
+        match scheme {
+            prover::CommitmentScheme::DynamicDory => Ok(Self::DynamicDory),
+            #[cfg(feature = "hyperkzg")]
+            prover::CommitmentScheme::HyperKzg => Ok(Self::HyperKzg),
+            _ => Err(UnsupportedCommitmentSchemeError { scheme }),
+        }
+    }
+}
+
+/// Errors that can occur when deserializing a verifier setup with
+/// [`CommitmentEvaluationProofId::deserialize_verifier_setup`].
+#[derive(Snafu, Debug)]
+pub enum VerifierSetupError<E: core::error::Error + 'static> {
+    /// The bytes are a verifier setup for another commitment scheme.
+    #[snafu(display("verifier setup is for scheme {actual} but {expected} was requested"))]
+    SchemeMismatch {
+        /// The scheme the setup was deserialized for
+        expected: CommitmentScheme,
+        /// The scheme the bytes are a setup for
+        actual: CommitmentScheme,
+    },
+    /// The bytes are not a verifier setup.
+    #[snafu(display("unable to deserialize verifier setup: {source}"))]
+    Deserialization {
+        /// Source of the error.
+        source: E,
+    },
+}
+
+/// Whether `bytes` are exactly a serialized Hyper KZG verifier setup.
+#[cfg(all(feature = "hyperkzg", feature = "native"))]
+pub(crate) fn is_hyper_kzg_verifier_setup(bytes: &[u8]) -> bool {
+    matches!(
+        proof_of_sql::base::try_standard_binary_deserialization::<VerifierKey<HyperKZGEngine>>(bytes),
+        Ok((_, read)) if read == bytes.len()
+    )
+}
+
 /// Trait for commitment evaluation proofs that defines their associated [`CommitmentScheme`].
 pub trait CommitmentEvaluationProofId:
     CommitmentEvaluationProof + Serialize + for<'de> Deserialize<'de>
@@ -77,16 +156,145 @@ impl CommitmentEvaluationProofId
 {
     const COMMITMENT_SCHEME: CommitmentScheme = CommitmentScheme::HyperKzg;
     const DEFAULT_VERIFIER_SETUP_BYTES: &'static [u8] = HYPER_KZG_VERIFIER_SETUP_BYTES;
-    type DeserializationError = bincode::error::DecodeError;
+    type DeserializationError = VerifierSetupError<bincode::error::DecodeError>;
 
+    /// A Dynamic Dory setup may start with bytes that deserialize as a Hyper KZG setup, so any
+    /// setup that does not consume all of `bytes` is checked for being a Dynamic Dory one.
     #[cfg(feature = "native")]
     fn deserialize_verifier_setup<'a>(
         bytes: &[u8],
         alloc: &'a Bump,
     ) -> Result<&'a VerifierKey<HyperKZGEngine>, Self::DeserializationError> {
-        let setup: VerifierKey<HyperKZGEngine> =
-            proof_of_sql::base::try_standard_binary_deserialization(bytes)
-                .map(|(setup, _)| setup)?;
+        let result = proof_of_sql::base::try_standard_binary_deserialization::<
+            VerifierKey<HyperKZGEngine>,
+        >(bytes);
+        if !matches!(result, Ok((_, read)) if read == bytes.len())
+            && super::dory_commitment_scheme::is_dynamic_dory_verifier_setup(bytes)
+        {
+            return Err(VerifierSetupError::SchemeMismatch {
+                expected: CommitmentScheme::HyperKzg,
+                actual: CommitmentScheme::DynamicDory,
+            });
+        }
+        let (setup, _) = result.map_err(|source| VerifierSetupError::Deserialization { source })?;
         Ok(alloc.alloc(setup) as &'a VerifierKey<HyperKZGEngine>)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn we_can_parse_commitment_schemes() {
+        assert_eq!(
+            "DynamicDory".parse::<CommitmentScheme>(),
+            Ok(CommitmentScheme::DynamicDory)
+        );
+        assert_eq!(
+            "DYNAMIC_DORY".parse::<CommitmentScheme>(),
+            Ok(CommitmentScheme::DynamicDory)
+        );
+        #[cfg(feature = "hyperkzg")]
+        assert_eq!(
+            "hyper-kzg".parse::<CommitmentScheme>(),
+            Ok(CommitmentScheme::HyperKzg)
+        );
+        assert_eq!(
+            "ipa".parse::<CommitmentScheme>(),
+            Err(UnknownCommitmentSchemeError {
+                scheme: "ipa".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn we_can_convert_prover_commitment_schemes() {
+        assert_eq!(
+            CommitmentScheme::try_from(prover::CommitmentScheme::DynamicDory),
+            Ok(CommitmentScheme::DynamicDory)
+        );
+        #[cfg(feature = "hyperkzg")]
+        assert_eq!(
+            CommitmentScheme::try_from(prover::CommitmentScheme::HyperKzg),
+            Ok(CommitmentScheme::HyperKzg)
+        );
+        assert_eq!(
+            CommitmentScheme::try_from(prover::CommitmentScheme::Ipa),
+            Err(UnsupportedCommitmentSchemeError {
+                scheme: prover::CommitmentScheme::Ipa
+            })
+        );
+
+        // Converting to the prover scheme and back is the identity
+        for scheme in [
+            CommitmentScheme::DynamicDory,
+            #[cfg(feature = "hyperkzg")]
+            CommitmentScheme::HyperKzg,
+        ] {
+            assert_eq!(
+                CommitmentScheme::try_from(prover::CommitmentScheme::from(scheme)),
+                Ok(scheme)
+            );
+        }
+    }
+
+    #[cfg(all(feature = "hyperkzg", feature = "native"))]
+    #[test]
+    fn we_detect_a_verifier_setup_for_the_other_scheme() {
+        use proof_of_sql::proof_primitive::{
+            dory::DynamicDoryEvaluationProof, hyperkzg::HyperKZGCommitmentEvaluationProof,
+        };
+
+        let bump = Bump::new();
+        assert!(DynamicDoryEvaluationProof::deserialize_verifier_setup(
+            DynamicDoryEvaluationProof::DEFAULT_VERIFIER_SETUP_BYTES,
+            &bump
+        )
+        .is_ok());
+        assert!(
+            HyperKZGCommitmentEvaluationProof::deserialize_verifier_setup(
+                HyperKZGCommitmentEvaluationProof::DEFAULT_VERIFIER_SETUP_BYTES,
+                &bump
+            )
+            .is_ok()
+        );
+
+        let err = HyperKZGCommitmentEvaluationProof::deserialize_verifier_setup(
+            DynamicDoryEvaluationProof::DEFAULT_VERIFIER_SETUP_BYTES,
+            &bump,
+        )
+        .unwrap_err();
+        assert!(matches!(
+            err,
+            VerifierSetupError::SchemeMismatch {
+                expected: CommitmentScheme::HyperKzg,
+                actual: CommitmentScheme::DynamicDory,
+            }
+        ));
+        assert_eq!(
+            err.to_string(),
+            "verifier setup is for scheme DynamicDory but HyperKzg was requested"
+        );
+
+        let err = DynamicDoryEvaluationProof::deserialize_verifier_setup(
+            HyperKZGCommitmentEvaluationProof::DEFAULT_VERIFIER_SETUP_BYTES,
+            &bump,
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "verifier setup is for scheme HyperKzg but DynamicDory was requested"
+        );
+
+        // Bytes that are no setup at all are still reported as such
+        assert!(matches!(
+            HyperKZGCommitmentEvaluationProof::deserialize_verifier_setup(&[0; 8], &bump),
+            Err(VerifierSetupError::Deserialization { .. })
+        ));
+        assert!(matches!(
+            DynamicDoryEvaluationProof::deserialize_verifier_setup(&[0; 8], &bump),
+            Err(VerifierSetupError::Deserialization { .. })
+        ));
+    }
+}
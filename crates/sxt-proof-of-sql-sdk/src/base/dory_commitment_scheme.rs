@@ -0,0 +1,48 @@
+#[cfg(all(feature = "hyperkzg", feature = "native"))]
+use super::commitment_scheme::is_hyper_kzg_verifier_setup;
+use super::{commitment_scheme::VerifierSetupError, CommitmentEvaluationProofId, CommitmentScheme};
+#[cfg(feature = "native")]
+use ark_serialize::{CanonicalDeserialize, Compress, Validate};
+#[cfg(feature = "native")]
+use bumpalo::Bump;
+use proof_of_sql::proof_primitive::dory::DynamicDoryEvaluationProof;
+#[cfg(feature = "native")]
+use proof_of_sql::proof_primitive::dory::VerifierSetup;
+
+// Default verifier setups for different commitment schemes.
+pub(crate) const DYNAMIC_DORY_VERIFIER_SETUP_BYTES: &[u8] = include_bytes!(concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/verifier_setups/dynamic-dory.bin"
+));
+
+impl CommitmentEvaluationProofId for DynamicDoryEvaluationProof {
+    const COMMITMENT_SCHEME: CommitmentScheme = CommitmentScheme::DynamicDory;
+    const DEFAULT_VERIFIER_SETUP_BYTES: &'static [u8] = DYNAMIC_DORY_VERIFIER_SETUP_BYTES;
+    type DeserializationError = VerifierSetupError<ark_serialize::SerializationError>;
+
+    #[cfg(feature = "native")]
+    fn deserialize_verifier_setup<'a>(
+        bytes: &[u8],
+        alloc: &'a Bump,
+    ) -> Result<&'a VerifierSetup, Self::DeserializationError> {
+        let setup = VerifierSetup::deserialize_with_mode(bytes, Compress::No, Validate::No)
+            .map_err(|source| {
+                #[cfg(feature = "hyperkzg")]
+                if is_hyper_kzg_verifier_setup(bytes) {
+                    return VerifierSetupError::SchemeMismatch {
+                        expected: CommitmentScheme::DynamicDory,
+                        actual: CommitmentScheme::HyperKzg,
+                    };
+                }
+                VerifierSetupError::Deserialization { source }
+            })?;
+        Ok(alloc.alloc(setup) as &'a VerifierSetup)
+    }
+}
+
+/// Whether `bytes` are exactly a serialized Dynamic Dory verifier setup.
+#[cfg(feature = "native")]
+pub(crate) fn is_dynamic_dory_verifier_setup(mut bytes: &[u8]) -> bool {
+    VerifierSetup::deserialize_with_mode(&mut bytes, Compress::No, Validate::No).is_ok()
+        && bytes.is_empty()
+}
@@ -30,6 +30,32 @@ pub(crate) struct Column<T> {
     pub(crate) column: Vec<T>,
 }
 
+/// A binary value, represented either as an array of bytes or as a `0x`-prefixed hex string.
+#[derive(Serialize, Debug, PartialEq, Deserialize)]
+#[serde(untagged)]
+pub(crate) enum JSFriendlyBinary {
+    Bytes(Vec<u8>),
+    Hex(String),
+}
+
+impl JSFriendlyBinary {
+    fn into_hex(self) -> Self {
+        match self {
+            JSFriendlyBinary::Bytes(bytes) => {
+                JSFriendlyBinary::Hex(format!("0x{}", hex::encode(bytes)))
+            }
+            hex @ JSFriendlyBinary::Hex(_) => hex,
+        }
+    }
+}
+
+/// Options controlling how verified results are represented as JSON.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct JsonResultOptions {
+    /// Emit `VarBinary` values as `0x`-prefixed hex strings instead of byte arrays.
+    pub varbinary_as_hex: bool,
+}
+
 /// A JavaScript-friendly representation of a proof of sql result column, converting larger integer types to strings.
 #[derive(Serialize, Debug, PartialEq, Deserialize)]
 #[serde(tag = "type")]
@@ -51,14 +77,49 @@ pub(crate) enum JSFriendlyColumn {
     /// Timestamp columns
     TimestampTZ(TimestampTZColumn),
     /// Variable length binary columns
-    VarBinary(Column<Vec<u8>>),
+    VarBinary(Column<JSFriendlyBinary>),
     /// Scalar columns
     Scalar(Column<String>),
 }
 
+/// The type of a result column, with the parameters of decimal and timestamp columns.
+#[derive(Serialize, Debug, PartialEq, Deserialize)]
+#[serde(tag = "type")]
+pub(crate) enum JSFriendlyColumnType {
+    Boolean,
+    TinyInt,
+    SmallInt,
+    Int,
+    BigInt,
+    VarChar,
+    Decimal75 {
+        precision: u8,
+        scale: i8,
+    },
+    #[serde(rename_all = "camelCase")]
+    TimestampTZ {
+        time_unit: PoSQLTimeUnit,
+        offset: i32,
+    },
+    VarBinary,
+    Scalar,
+}
+
+/// The name and type of a result column.
+#[derive(Serialize, Debug, PartialEq, Deserialize)]
+pub(crate) struct ColumnSchema {
+    name: String,
+    #[serde(flatten)]
+    column_type: JSFriendlyColumnType,
+}
+
 #[derive(Serialize, Debug, Deserialize, PartialEq)]
 pub(crate) struct Success<T> {
     result: T,
+    /// The columns of `result` in order, so consumers can build a schema without inspecting the
+    /// columns themselves.
+    #[serde(default)]
+    schema: Vec<ColumnSchema>,
 }
 
 #[derive(Serialize, Debug, Deserialize, PartialEq)]
@@ -69,6 +130,7 @@ pub(crate) enum Failure {
     VerificationError(String),
     TypeConversion(String),
     Serialization(String),
+    QueryError(String),
 }
 
 #[derive(Serialize, Debug, Deserialize, PartialEq)]
@@ -133,9 +195,9 @@ impl TryFrom<OwnedColumn<BNScalar>> for JSFriendlyColumn {
                         .collect::<Vec<_>>(),
                 }))
             }
-            OwnedColumn::VarBinary(items) => {
-                Ok(JSFriendlyColumn::VarBinary(Column { column: items }))
-            }
+            OwnedColumn::VarBinary(items) => Ok(JSFriendlyColumn::VarBinary(Column {
+                column: items.into_iter().map(JSFriendlyBinary::Bytes).collect(),
+            })),
             OwnedColumn::Scalar(items) => Ok(JSFriendlyColumn::Scalar(Column {
                 column: scalar_to_string(items),
             })),
@@ -147,15 +209,56 @@ impl TryFrom<OwnedColumn<BNScalar>> for JSFriendlyColumn {
     }
 }
 
+impl JSFriendlyColumn {
+    /// The type of this column.
+    fn column_type(&self) -> JSFriendlyColumnType {
+        match self {
+            JSFriendlyColumn::Boolean(_) => JSFriendlyColumnType::Boolean,
+            JSFriendlyColumn::TinyInt(_) => JSFriendlyColumnType::TinyInt,
+            JSFriendlyColumn::SmallInt(_) => JSFriendlyColumnType::SmallInt,
+            JSFriendlyColumn::Int(_) => JSFriendlyColumnType::Int,
+            JSFriendlyColumn::BigInt(_) => JSFriendlyColumnType::BigInt,
+            JSFriendlyColumn::VarChar(_) => JSFriendlyColumnType::VarChar,
+            JSFriendlyColumn::Decimal75(Decimal75Column {
+                precision, scale, ..
+            }) => JSFriendlyColumnType::Decimal75 {
+                precision: *precision,
+                scale: *scale,
+            },
+            JSFriendlyColumn::TimestampTZ(TimestampTZColumn {
+                time_unit, offset, ..
+            }) => JSFriendlyColumnType::TimestampTZ {
+                time_unit: *time_unit,
+                offset: *offset,
+            },
+            JSFriendlyColumn::VarBinary(_) => JSFriendlyColumnType::VarBinary,
+            JSFriendlyColumn::Scalar(_) => JSFriendlyColumnType::Scalar,
+        }
+    }
+
+    /// Apply the representation choices in `options` to this column.
+    fn with_options(self, options: &JsonResultOptions) -> Self {
+        match self {
+            JSFriendlyColumn::VarBinary(Column { column }) if options.varbinary_as_hex => {
+                JSFriendlyColumn::VarBinary(Column {
+                    column: column.into_iter().map(JSFriendlyBinary::into_hex).collect(),
+                })
+            }
+            column => column,
+        }
+    }
+}
+
 /// Convert a result table to a javascript friendly value. This handles converting bigger integer types to string for easier handling by javascript.
 pub(crate) fn try_convert_table_to_javascript_friendly_table(
     table: OwnedTable<BNScalar>,
+    options: &JsonResultOptions,
 ) -> Result<IndexMap<String, JSFriendlyColumn>, Failure> {
     table
         .into_inner()
         .into_iter()
         .map(|(key, column)| {
-            let js_friendly_column = JSFriendlyColumn::try_from(column)?;
+            let js_friendly_column = JSFriendlyColumn::try_from(column)?.with_options(options);
             Ok((key.to_string(), js_friendly_column))
         })
         .collect()
@@ -166,7 +269,17 @@ impl From<Result<IndexMap<String, JSFriendlyColumn>, Failure>>
 {
     fn from(value: Result<IndexMap<String, JSFriendlyColumn>, Failure>) -> Self {
         value.map_or_else(VerificationStatus::Failure, |success| {
-            VerificationStatus::Success(Success { result: success })
+            let schema = success
+                .iter()
+                .map(|(name, column)| ColumnSchema {
+                    name: name.clone(),
+                    column_type: column.column_type(),
+                })
+                .collect();
+            VerificationStatus::Success(Success {
+                result: success,
+                schema,
+            })
         })
     }
 }
@@ -175,7 +288,8 @@ impl From<Result<IndexMap<String, JSFriendlyColumn>, Failure>>
 mod tests {
     use crate::base::javascript_friendly_types::{
         try_convert_table_to_javascript_friendly_table, Column, Decimal75Column, Failure,
-        JSFriendlyColumn, Success, TimestampTZColumn, VerificationStatus,
+        JSFriendlyBinary, JSFriendlyColumn, JsonResultOptions, TimestampTZColumn,
+        VerificationStatus,
     };
     use indexmap::IndexMap;
     use proof_of_sql::{
@@ -315,12 +429,38 @@ mod tests {
         let js_friendly_column =
             JSFriendlyColumn::try_from(varbinary_column).expect("Conversion failed");
         if let JSFriendlyColumn::VarBinary(varbinary_col) = js_friendly_column {
-            assert_eq!(varbinary_col.column, vec![vec![1, 2, 3], vec![4, 5, 6]]);
+            assert_eq!(
+                varbinary_col.column,
+                vec![
+                    JSFriendlyBinary::Bytes(vec![1, 2, 3]),
+                    JSFriendlyBinary::Bytes(vec![4, 5, 6])
+                ]
+            );
         } else {
             panic!("Expected VarBinary column");
         }
     }
 
+    #[test]
+    fn we_can_convert_varbinary_columns_to_hex_strings() {
+        let mut result = IndexMap::new();
+        result.insert(
+            Ident::new("varbinary_col"),
+            OwnedColumn::VarBinary(vec![vec![1, 2, 3], vec![]]),
+        );
+        let result = try_convert_table_to_javascript_friendly_table(
+            OwnedTable::try_new(result.into_iter().collect()).unwrap(),
+            &JsonResultOptions {
+                varbinary_as_hex: true,
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            serde_json::to_string(&result).unwrap(),
+            r#"{"varbinary_col":{"type":"VarBinary","column":["0x010203","0x"]}}"#
+        );
+    }
+
     #[test]
     fn test_js_friendly_scalar_column_conversion() {
         let scalar_column = OwnedColumn::Scalar(vec![BNScalar::from(42), BNScalar::from(-99)]);
@@ -353,6 +493,7 @@ mod tests {
 
         let result = try_convert_table_to_javascript_friendly_table(
             OwnedTable::try_new(result.into_iter().collect()).unwrap(),
+            &JsonResultOptions::default(),
         )
         .unwrap();
         let expected_result = indexmap::indexmap! {
@@ -370,6 +511,7 @@ mod tests {
         result.insert(Ident::new("unsupported_col"), col.clone());
         let failure = try_convert_table_to_javascript_friendly_table(
             OwnedTable::try_new(result.into_iter().collect()).unwrap(),
+            &JsonResultOptions::default(),
         )
         .unwrap_err();
         assert!(
@@ -377,10 +519,54 @@ mod tests {
         );
     }
 
+    #[test]
+    fn we_can_describe_the_schema_of_a_mixed_type_result() {
+        let table = OwnedTable::try_new(
+            [
+                (Ident::new("ID"), OwnedColumn::BigInt(vec![1])),
+                (
+                    Ident::new("PRICE"),
+                    OwnedColumn::Decimal75(
+                        Precision::new(10).unwrap(),
+                        2,
+                        vec![BNScalar::from(12345)],
+                    ),
+                ),
+                (
+                    Ident::new("TIME"),
+                    OwnedColumn::TimestampTZ(
+                        PoSQLTimeUnit::Microsecond,
+                        PoSQLTimeZone::utc(),
+                        vec![0],
+                    ),
+                ),
+                (Ident::new("DATA"), OwnedColumn::VarBinary(vec![vec![1]])),
+            ]
+            .into_iter()
+            .collect(),
+        )
+        .unwrap();
+
+        let status = VerificationStatus::from(try_convert_table_to_javascript_friendly_table(
+            table,
+            &JsonResultOptions::default(),
+        ));
+
+        let serialized = serde_json::to_value(&status).unwrap();
+        assert_eq!(
+            serialized["schema"],
+            serde_json::json!([
+                { "name": "ID", "type": "BigInt" },
+                { "name": "PRICE", "type": "Decimal75", "precision": 10, "scale": 2 },
+                { "name": "TIME", "type": "TimestampTZ", "timeUnit": "Microsecond", "offset": 0 },
+                { "name": "DATA", "type": "VarBinary" },
+            ])
+        );
+    }
+
     #[test]
     fn serilization_works_as_expected() {
-        let res = VerificationStatus::Success(Success {
-            result: indexmap::indexmap! {
+        let res = VerificationStatus::from(Ok(indexmap::indexmap! {
                 "BOOLEAN_COLUMN".to_string() => JSFriendlyColumn::Boolean(Column{column: vec![false]}),
                 "TINYINT_COLUMN".to_string() => JSFriendlyColumn::TinyInt(Column{column: vec![1]}),
                 "SMALLINT_COLUMN".to_string() => JSFriendlyColumn::SmallInt(Column{column: vec![100]}),
@@ -389,10 +575,9 @@ mod tests {
                 "VARCHAR_COLUMN".to_string() => JSFriendlyColumn::VarChar(Column{column: vec!["test".to_string()]}),
                 "DECIMAL75_COLUMN".to_string() => JSFriendlyColumn::Decimal75(Decimal75Column{ precision: 4, scale: 1, column: vec!["22432845".to_string()] }),
                 "TIMESTAMPTZ_COLUMN".to_string() => JSFriendlyColumn::TimestampTZ(TimestampTZColumn{time_unit: PoSQLTimeUnit::Millisecond, offset: 0, column: vec!["22432845".to_string()]}),
-                "VARBINARY_COLUMN".to_string() => JSFriendlyColumn::VarBinary(Column{column: vec![vec![1,2,3,4,5]]}),
+                "VARBINARY_COLUMN".to_string() => JSFriendlyColumn::VarBinary(Column{column: vec![JSFriendlyBinary::Bytes(vec![1,2,3,4,5])]}),
                 "SCALAR_COLUMN".to_string() => JSFriendlyColumn::Scalar(Column{column: vec!["22432845".to_string()]}),
-            },
-        });
+        }));
         let serialized = serde_json::to_string(&res).unwrap();
         let expected_result: VerificationStatus<IndexMap<String, JSFriendlyColumn>> =
             serde_json::from_str(&serialized).unwrap();
@@ -418,6 +603,7 @@ mod tests {
             )
             .into(),
             Failure::Serialization("Failed to serialize result".to_string()).into(),
+            Failure::QueryError("Failed to run query".to_string()).into(),
         ];
         let serialized = serde_json::to_string(&res).unwrap();
         let expected_result: Vec<VerificationStatus<IndexMap<String, JSFriendlyColumn>>> =
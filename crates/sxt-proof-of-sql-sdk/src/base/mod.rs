@@ -1,22 +1,44 @@
 #![doc = include_str!("README.md")]
 
 pub(crate) mod serde;
+pub use self::serde::hex::MAX_HEX_DECODED_LEN;
 
 mod commitment_scheme;
-pub use commitment_scheme::{CommitmentEvaluationProofId, CommitmentScheme};
+pub use commitment_scheme::{
+    CommitmentEvaluationProofId, CommitmentScheme, UnknownCommitmentSchemeError,
+    UnsupportedCommitmentSchemeError, VerifierSetupError,
+};
+
+#[cfg(any(feature = "native", feature = "wasm-dory"))]
+mod dory_commitment_scheme;
 
 #[cfg(feature = "hyperkzg")]
-mod javascript_friendly_types;
+pub(crate) mod javascript_friendly_types;
+#[cfg(feature = "hyperkzg")]
+pub use javascript_friendly_types::JsonResultOptions;
+
+mod sql;
+pub use sql::{parse_single_statement, ParseSqlError};
 
 mod uppercase_accessor;
-pub use uppercase_accessor::{uppercase_table_ref, UppercaseAccessor};
+pub use uppercase_accessor::{
+    canonicalize_identifier, canonicalize_table_ref, uppercase_table_ref, CaseAccessor,
+    IdentCasing, UppercaseAccessor,
+};
 
 mod verify;
 #[cfg(feature = "hyperkzg")]
-pub use verify::proof_of_sql_verify_from_json_responses;
 pub use verify::{
-    verify_from_zk_query_and_substrate_responses, verify_prover_via_gateway_response,
-    VerifyProverResponseError,
+    proof_of_sql_verify_from_json_responses, proof_of_sql_verify_from_json_responses_with_options,
+};
+pub use verify::{
+    verify_attestations_and_extract, verify_from_zk_query_and_substrate_responses,
+    verify_from_zk_query_and_substrate_responses_detailed,
+    verify_from_zk_query_and_substrate_responses_with_params,
+    verify_from_zk_query_and_substrate_responses_with_plan,
+    verify_from_zk_query_response_without_attestations, verify_prover_via_gateway_response,
+    verify_prover_via_gateway_response_dyn, verify_prover_via_gateway_response_with_claimed_result,
+    VerifiedResult, VerifyProverResponseError,
 };
 
 /// code for interacting with the prover service
@@ -1,8 +1,31 @@
 use hex::FromHexError;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
+/// Cap on the number of bytes a single hex string may decode to when deserializing.
+///
+/// This is only a secondary guard: by the time a hex string is checked, it has already been
+/// buffered. The native client bounds whole response bodies before deserializing them, see
+/// `ZkQueryClient::max_response_bytes`.
+pub const MAX_HEX_DECODED_LEN: usize = 256 * 1024 * 1024;
+
+/// Decode a hex string with optional "0x" prefix, rejecting it before decoding if it would exceed
+/// [`MAX_HEX_DECODED_LEN`].
+fn from_hex_capped<E: serde::de::Error>(hex: &str) -> Result<Vec<u8>, E> {
+    from_hex_with_max(hex, MAX_HEX_DECODED_LEN)
+}
+
+fn from_hex_with_max<E: serde::de::Error>(hex: &str, max: usize) -> Result<Vec<u8>, E> {
+    let decoded_len = hex.strip_prefix("0x").unwrap_or(hex).len() / 2;
+    if decoded_len > max {
+        return Err(E::custom(format!(
+            "hex value decodes to {decoded_len} bytes, exceeding the maximum of {max}"
+        )));
+    }
+    from_hex(hex).map_err(E::custom)
+}
+
 /// Function for decoding a hex string with optional "0x" prefix into bytes.
-fn from_hex(hex: &str) -> Result<Vec<u8>, FromHexError> {
+pub(crate) fn from_hex(hex: &str) -> Result<Vec<u8>, FromHexError> {
     hex::decode(hex.strip_prefix("0x").unwrap_or(hex))
 }
 
@@ -57,7 +80,7 @@ where
     D: Deserializer<'de>,
 {
     let b = String::deserialize(deserializer)?;
-    from_hex(&b).map_err(serde::de::Error::custom)
+    from_hex_capped(&b)
 }
 
 /// Hex deserialization function.
@@ -124,7 +147,7 @@ where
     let bytes_array = Vec::<String>::deserialize(deserializer)?;
     bytes_array
         .into_iter()
-        .map(|b| from_hex(&b).map_err(serde::de::Error::custom))
+        .map(|b| from_hex_capped(&b))
         .collect()
 }
 
@@ -187,6 +210,20 @@ mod tests {
         assert_eq!(bytes_array, deserialized);
     }
 
+    #[test]
+    fn we_cannot_deserialize_hex_over_the_size_cap() {
+        use super::from_hex_with_max;
+
+        assert_eq!(
+            from_hex_with_max::<serde_json::Error>("0x0128", 2).unwrap(),
+            vec![1u8, 40]
+        );
+        assert!(from_hex_with_max::<serde_json::Error>("0x012801", 2)
+            .unwrap_err()
+            .to_string()
+            .contains("exceeding the maximum of 2"));
+    }
+
     #[cfg(feature = "hyperkzg")]
     #[test]
     fn we_can_retrieve_address_from_hex() {
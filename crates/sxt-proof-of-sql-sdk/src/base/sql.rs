@@ -0,0 +1,68 @@
+use snafu::Snafu;
+use sqlparser::{
+    ast::Statement,
+    dialect::GenericDialect,
+    parser::{Parser, ParserError},
+};
+
+/// Errors that can occur when parsing sql query text.
+#[derive(Snafu, Debug, PartialEq)]
+pub enum ParseSqlError {
+    /// Unable to parse sql.
+    #[snafu(display("unable to parse sql: {error}"))]
+    Parse { error: ParserError },
+    /// The sql contained no statements.
+    #[snafu(display("query contains no sql statements"))]
+    EmptyQuery,
+    /// The sql contained more than one statement.
+    #[snafu(display("expected a single sql statement, found {count}"))]
+    MultipleStatements { count: usize },
+}
+
+impl From<ParserError> for ParseSqlError {
+    fn from(error: ParserError) -> Self {
+        ParseSqlError::Parse { error }
+    }
+}
+
+/// Parse sql query text that must contain exactly one statement.
+///
+/// Input such as `SELECT 1; DROP TABLE x;` is rejected instead of silently using the first statement.
+pub fn parse_single_statement(sql: &str) -> Result<Statement, ParseSqlError> {
+    let mut statements = Parser::parse_sql(&GenericDialect {}, sql)?;
+    match statements.len() {
+        0 => Err(ParseSqlError::EmptyQuery),
+        1 => Ok(statements.remove(0)),
+        count => Err(ParseSqlError::MultipleStatements { count }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn we_can_parse_a_single_statement() {
+        assert!(matches!(
+            parse_single_statement("SELECT * FROM ethereum.blocks;"),
+            Ok(Statement::Query(_))
+        ));
+    }
+
+    #[test]
+    fn we_cannot_parse_multiple_statements() {
+        assert_eq!(
+            parse_single_statement("SELECT 1; DROP TABLE x;"),
+            Err(ParseSqlError::MultipleStatements { count: 2 })
+        );
+    }
+
+    #[test]
+    fn we_cannot_parse_empty_or_invalid_sql() {
+        assert_eq!(parse_single_statement(" "), Err(ParseSqlError::EmptyQuery));
+        assert!(matches!(
+            parse_single_statement("SELEC 1"),
+            Err(ParseSqlError::Parse { .. })
+        ));
+    }
+}
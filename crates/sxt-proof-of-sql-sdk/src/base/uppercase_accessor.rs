@@ -4,62 +4,175 @@ use proof_of_sql::base::{
 };
 use sqlparser::ast::Ident;
 
-fn uppercase_identifier(ident: Ident) -> Ident {
-    let value = ident.value.to_uppercase();
-    Ident { value, ..ident }
+/// Canonicalize an identifier to the form sxt-chain stores it in.
+///
+/// The value is uppercased and the quote style is dropped, so `blocks`, `BLOCKS` and `"Blocks"`
+/// all compare equal once canonicalized.
+pub fn canonicalize_identifier(ident: &Ident) -> Ident {
+    Ident::new(ident.value.to_uppercase())
 }
 
-pub fn uppercase_table_ref(table_ref: TableRef) -> TableRef {
+/// Canonicalize a [`TableRef`] to the form sxt-chain stores it in.
+///
+/// This is the single casing contract used when matching query tables against chain commitments:
+/// both the schema and table identifiers are canonicalized with [`canonicalize_identifier`].
+pub fn canonicalize_table_ref(table_ref: &TableRef) -> TableRef {
     TableRef::from_idents(
-        table_ref.schema_id().cloned().map(uppercase_identifier),
-        uppercase_identifier(table_ref.table_id().clone()),
+        table_ref.schema_id().map(canonicalize_identifier),
+        canonicalize_identifier(table_ref.table_id()),
     )
 }
 
-/// Generic wrapper of proof-of-sql `-Accessor` types that coerces to uppercase.
+/// Uppercase a [`TableRef`]. Equivalent to [`canonicalize_table_ref`].
+pub fn uppercase_table_ref(table_ref: TableRef) -> TableRef {
+    canonicalize_table_ref(&table_ref)
+}
+
+/// How identifiers in a query are normalized before planning against commitments.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum IdentCasing {
+    /// Uppercase every identifier, matching how sxt-chain stores them.
+    #[default]
+    Upper,
+    /// Lowercase unquoted identifiers, for schemas with lowercase names.
+    Lower,
+    /// Use identifiers exactly as written, for case-sensitive schemas.
+    Preserve,
+}
+
+impl IdentCasing {
+    /// Normalize an identifier. Quoted identifiers keep their case under [`IdentCasing::Lower`].
+    pub fn apply(self, ident: &Ident) -> Ident {
+        match self {
+            IdentCasing::Upper => canonicalize_identifier(ident),
+            IdentCasing::Lower if ident.quote_style.is_none() => {
+                Ident::new(ident.value.to_lowercase())
+            }
+            IdentCasing::Lower | IdentCasing::Preserve => Ident::new(ident.value.clone()),
+        }
+    }
+
+    /// Normalize both identifiers of a [`TableRef`] with [`IdentCasing::apply`].
+    pub fn apply_to_table_ref(self, table_ref: &TableRef) -> TableRef {
+        TableRef::from_idents(
+            table_ref.schema_id().map(|schema_id| self.apply(schema_id)),
+            self.apply(table_ref.table_id()),
+        )
+    }
+}
+
+/// Generic wrapper of proof-of-sql `-Accessor` types that normalizes the casing of lookups.
+///
+/// Every table ref and column identifier is passed through [`IdentCasing::apply`] before it
+/// reaches the wrapped accessor, so the wrapped accessor only needs to be keyed in that casing.
 ///
 /// Sxt-chain generally stores identifiers in all uppercase.
 /// The SDK uses accessors in this casing due to using a `QueryCommitments` built from chain data.
 /// So, this wrapper helps bridge the gap between the casing of queries/proof plans to chain data.
 #[derive(Clone)]
+pub struct CaseAccessor<'a, A> {
+    /// The wrapped accessor
+    pub accessor: &'a A,
+    /// The casing the wrapped accessor is keyed in
+    pub casing: IdentCasing,
+}
+
+/// Generic wrapper of proof-of-sql `-Accessor` types that coerces to uppercase.
+///
+/// Lookups go through [`canonicalize_table_ref`] and [`canonicalize_identifier`]. This is a
+/// [`CaseAccessor`] with [`IdentCasing::Upper`].
+#[derive(Clone)]
 pub struct UppercaseAccessor<'a, A>(pub &'a A);
 
-impl<SA> SchemaAccessor for UppercaseAccessor<'_, SA>
+impl<'a, A> UppercaseAccessor<'a, A> {
+    fn case_accessor(&self) -> CaseAccessor<'a, A> {
+        CaseAccessor::uppercase(self.0)
+    }
+}
+
+impl<'a, A> CaseAccessor<'a, A> {
+    /// Wrap `accessor`, normalizing lookups with `casing`.
+    pub fn new(accessor: &'a A, casing: IdentCasing) -> Self {
+        Self { accessor, casing }
+    }
+
+    /// Wrap `accessor`, uppercasing lookups.
+    pub fn uppercase(accessor: &'a A) -> Self {
+        Self::new(accessor, IdentCasing::Upper)
+    }
+}
+
+impl<SA> SchemaAccessor for CaseAccessor<'_, SA>
 where
     SA: SchemaAccessor,
 {
     fn lookup_column(&self, table_ref: &TableRef, column_id: &Ident) -> Option<ColumnType> {
-        let ident = uppercase_identifier(column_id.clone());
-        self.0
-            .lookup_column(&uppercase_table_ref(table_ref.clone()), &ident)
+        self.accessor.lookup_column(
+            &self.casing.apply_to_table_ref(table_ref),
+            &self.casing.apply(column_id),
+        )
     }
 
     fn lookup_schema(&self, table_ref: &TableRef) -> Vec<(Ident, ColumnType)> {
-        self.0
-            .lookup_schema(&uppercase_table_ref(table_ref.clone()))
+        self.accessor
+            .lookup_schema(&self.casing.apply_to_table_ref(table_ref))
             .into_iter()
-            .map(|(ident, column_type)| {
-                let ident = uppercase_identifier(ident);
-                (ident, column_type)
-            })
+            .map(|(ident, column_type)| (self.casing.apply(&ident), column_type))
             .collect()
     }
 }
 
-impl<MA> MetadataAccessor for UppercaseAccessor<'_, MA>
+impl<SA> SchemaAccessor for UppercaseAccessor<'_, SA>
+where
+    SA: SchemaAccessor,
+{
+    fn lookup_column(&self, table_ref: &TableRef, column_id: &Ident) -> Option<ColumnType> {
+        self.case_accessor().lookup_column(table_ref, column_id)
+    }
+
+    fn lookup_schema(&self, table_ref: &TableRef) -> Vec<(Ident, ColumnType)> {
+        self.case_accessor().lookup_schema(table_ref)
+    }
+}
+
+impl<MA> MetadataAccessor for CaseAccessor<'_, MA>
 where
     MA: MetadataAccessor,
 {
     fn get_length(&self, table_ref: &TableRef) -> usize {
-        let table_ref = uppercase_table_ref(table_ref.clone());
+        self.accessor
+            .get_length(&self.casing.apply_to_table_ref(table_ref))
+    }
 
-        self.0.get_length(&table_ref)
+    fn get_offset(&self, table_ref: &TableRef) -> usize {
+        self.accessor
+            .get_offset(&self.casing.apply_to_table_ref(table_ref))
+    }
+}
+
+impl<MA> MetadataAccessor for UppercaseAccessor<'_, MA>
+where
+    MA: MetadataAccessor,
+{
+    fn get_length(&self, table_ref: &TableRef) -> usize {
+        self.case_accessor().get_length(table_ref)
     }
 
     fn get_offset(&self, table_ref: &TableRef) -> usize {
-        let table_ref = uppercase_table_ref(table_ref.clone());
+        self.case_accessor().get_offset(table_ref)
+    }
+}
 
-        self.0.get_offset(&table_ref)
+impl<CA, C> CommitmentAccessor<C> for CaseAccessor<'_, CA>
+where
+    CA: CommitmentAccessor<C>,
+    C: Commitment,
+{
+    fn get_commitment(&self, table_ref: &TableRef, column_id: &Ident) -> C {
+        self.accessor.get_commitment(
+            &self.casing.apply_to_table_ref(table_ref),
+            &self.casing.apply(column_id),
+        )
     }
 }
 
@@ -69,10 +182,7 @@ where
     C: Commitment,
 {
     fn get_commitment(&self, table_ref: &TableRef, column_id: &Ident) -> C {
-        self.0.get_commitment(
-            &uppercase_table_ref(table_ref.clone()),
-            &uppercase_identifier(column_id.clone()),
-        )
+        self.case_accessor().get_commitment(table_ref, column_id)
     }
 }
 
@@ -140,4 +250,87 @@ mod tests {
             commitment.column_commitments().commitments()[0]
         );
     }
+
+    #[test]
+    fn we_can_canonicalize_table_refs_regardless_of_casing_or_quoting() {
+        let lowercase: TableRef = "ethereum.blocks".parse().unwrap();
+        let quoted = TableRef::from_idents(
+            Some(Ident::with_quote('"', "Ethereum")),
+            Ident::with_quote('"', "Blocks"),
+        );
+        let uppercase: TableRef = "ETHEREUM.BLOCKS".parse().unwrap();
+
+        assert_eq!(canonicalize_table_ref(&lowercase), uppercase);
+        assert_eq!(canonicalize_table_ref(&quoted), uppercase);
+        assert_eq!(canonicalize_table_ref(&uppercase), uppercase);
+        assert_eq!(uppercase_table_ref(quoted), uppercase);
+    }
+
+    #[test]
+    fn we_can_get_lowercase_items_from_mixed_case() {
+        let public_parameters = PublicParameters::test_rand(2, &mut rand::thread_rng());
+        let setup = ProverSetup::from(&public_parameters);
+
+        let lowercase_col = Ident::new("col");
+        let mixed_case_col = Ident::new("Col");
+
+        let lowercase_table_ref: TableRef = "schema.table".parse().unwrap();
+        let mixed_case_table_ref: TableRef = "Schema.TABLE".parse().unwrap();
+
+        let commitment = TableCommitment::try_from_columns_with_offset(
+            [(
+                &lowercase_col,
+                &OwnedColumn::<DoryScalar>::Boolean(vec![false]),
+            )],
+            0,
+            &&setup,
+        )
+        .unwrap();
+
+        let accessor = QueryCommitments::<DynamicDoryCommitment>::from_iter([(
+            lowercase_table_ref,
+            commitment.clone(),
+        )]);
+        let lowercase_accessor = CaseAccessor::new(&accessor, IdentCasing::Lower);
+
+        assert_eq!(
+            UppercaseAccessor(&accessor).lookup_column(&mixed_case_table_ref, &mixed_case_col),
+            None
+        );
+        assert_eq!(
+            lowercase_accessor.lookup_column(&mixed_case_table_ref, &mixed_case_col),
+            Some(ColumnType::Boolean)
+        );
+        assert_eq!(
+            lowercase_accessor.lookup_schema(&mixed_case_table_ref),
+            vec![(lowercase_col.clone(), ColumnType::Boolean)]
+        );
+        assert_eq!(lowercase_accessor.get_length(&mixed_case_table_ref), 1);
+        assert_eq!(lowercase_accessor.get_offset(&mixed_case_table_ref), 0);
+        assert_eq!(
+            lowercase_accessor.get_commitment(&mixed_case_table_ref, &mixed_case_col),
+            commitment.column_commitments().commitments()[0]
+        );
+    }
+
+    #[test]
+    fn we_can_normalize_table_refs_with_each_casing() {
+        let table_ref = TableRef::from_idents(
+            Some(Ident::new("Ethereum")),
+            Ident::with_quote('"', "Blocks"),
+        );
+
+        assert_eq!(
+            IdentCasing::Upper.apply_to_table_ref(&table_ref),
+            "ETHEREUM.BLOCKS".parse().unwrap()
+        );
+        assert_eq!(
+            IdentCasing::Lower.apply_to_table_ref(&table_ref),
+            "ethereum.Blocks".parse().unwrap()
+        );
+        assert_eq!(
+            IdentCasing::Preserve.apply_to_table_ref(&table_ref),
+            "Ethereum.Blocks".parse().unwrap()
+        );
+    }
 }
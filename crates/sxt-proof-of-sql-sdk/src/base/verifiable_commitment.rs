@@ -1,13 +1,16 @@
 use super::{
-    commitment_scheme::CommitmentScheme, zk_query_models::TableCommitmentWithProof,
-    CommitmentEvaluationProofId,
+    canonicalize_table_ref, commitment_scheme::CommitmentScheme,
+    zk_query_models::TableCommitmentWithProof, CommitmentEvaluationProofId,
 };
+use eth_merkle_tree::utils::{errors::BytesError, keccak::keccak256};
 use indexmap::IndexMap;
 use proof_of_sql::base::{
-    commitment::{CommitmentEvaluationProof, QueryCommitments, TableCommitment},
+    commitment::{Commitment, CommitmentEvaluationProof, QueryCommitments, TableCommitment},
     database::TableRef,
     try_standard_binary_deserialization,
 };
+use snafu::Snafu;
+
 /// Adapted from attestation tree code in `sxt-node`
 /// This replicates the exact encoding logic from [`CommitmentMapPrefixFoliate`]
 ///
@@ -33,7 +36,23 @@ pub fn generate_commitment_leaf(
         .collect()
 }
 
+/// Hash a commitment leaf built by [`generate_commitment_leaf`] into the form used in the
+/// attestation Merkle tree.
+pub fn commitment_leaf_hash(
+    table_identifier: String,
+    commitment_scheme: CommitmentScheme,
+    table_commitment_bytes: Vec<u8>,
+) -> Result<String, BytesError> {
+    keccak256(&hex::encode(generate_commitment_leaf(
+        table_identifier,
+        commitment_scheme,
+        table_commitment_bytes,
+    )))
+}
+
 /// Extract [`QueryCommitments`] from an index map of [`TableCommitment`]s.
+///
+/// Table refs are keyed by their [`canonicalize_table_ref`] form.
 #[expect(clippy::type_complexity)]
 pub fn extract_query_commitments_from_table_commitments_with_proof<
     CPI: CommitmentEvaluationProofId,
@@ -55,8 +74,9 @@ pub fn extract_query_commitments_from_table_commitments_with_proof<
                     ),
                     Box<dyn core::error::Error>,
                 > {
-                    let table_ref =
-                        TableRef::try_from(table_id.as_str()).map_err(|err| err.to_string())?;
+                    let table_ref = canonicalize_table_ref(
+                        &TableRef::try_from(table_id.as_str()).map_err(|err| err.to_string())?,
+                    );
                     let table_commitment: TableCommitment<
                         <CPI as CommitmentEvaluationProof>::Commitment,
                     > = try_standard_binary_deserialization(
@@ -70,6 +90,68 @@ pub fn extract_query_commitments_from_table_commitments_with_proof<
     Ok(query_commitments)
 }
 
+/// A difference between two sets of [`QueryCommitments`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CommitmentDiff {
+    /// The table only has a commitment in the first set.
+    OnlyInFirst(TableRef),
+    /// The table only has a commitment in the second set.
+    OnlyInSecond(TableRef),
+    /// Both sets have a commitment for the table, but they differ.
+    Differs(TableRef),
+}
+
+/// Compare two sets of [`QueryCommitments`], e.g. to check that two sources agree.
+///
+/// Tables missing from `b` and differing tables are reported in the order of `a`, followed by
+/// the tables missing from `a` in the order of `b`. Equal sets produce no differences.
+pub fn diff_query_commitments<C: Commitment>(
+    a: &QueryCommitments<C>,
+    b: &QueryCommitments<C>,
+) -> Vec<CommitmentDiff> {
+    a.iter()
+        .filter_map(|(table_ref, commitment)| match b.get(table_ref) {
+            None => Some(CommitmentDiff::OnlyInFirst(table_ref.clone())),
+            Some(other) if other != commitment => Some(CommitmentDiff::Differs(table_ref.clone())),
+            Some(_) => None,
+        })
+        .chain(
+            b.keys()
+                .filter(|table_ref| !a.contains_key(*table_ref))
+                .map(|table_ref| CommitmentDiff::OnlyInSecond(table_ref.clone())),
+        )
+        .collect()
+}
+
+/// Error returned by [`merge_query_commitments`] when both sets commit to a table differently.
+#[derive(Snafu, Debug, PartialEq, Eq)]
+#[snafu(display("conflicting commitments for table {table}"))]
+pub struct ConflictingCommitmentsError {
+    /// The table with conflicting commitments
+    pub table: TableRef,
+}
+
+/// Combine two sets of [`QueryCommitments`].
+///
+/// Tables present in both sets must have equal commitments.
+pub fn merge_query_commitments<C: Commitment>(
+    mut a: QueryCommitments<C>,
+    b: QueryCommitments<C>,
+) -> Result<QueryCommitments<C>, ConflictingCommitmentsError> {
+    for (table_ref, commitment) in b {
+        match a.get(&table_ref) {
+            Some(existing) if *existing != commitment => {
+                return Err(ConflictingCommitmentsError { table: table_ref });
+            }
+            Some(_) => {}
+            None => {
+                a.insert(table_ref, commitment);
+            }
+        }
+    }
+    Ok(a)
+}
+
 #[cfg(test)]
 mod tests {
     #[cfg(feature = "hyperkzg")]
@@ -78,23 +160,12 @@ mod tests {
     use proof_of_sql::proof_primitive::hyperkzg::HyperKZGCommitmentEvaluationProof;
 
     #[cfg(feature = "hyperkzg")]
-    #[test]
-    fn test_generate_commitment_leaf() {
-        let table_identifier = "ETHEREUM.BLOCKS".to_string();
-        let commitment_scheme = CommitmentScheme::HyperKzg;
-        let table_commitment_bytes = vec![1, 2, 3, 4]; // Simple test data
-        let actual =
-            generate_commitment_leaf(table_identifier, commitment_scheme, table_commitment_bytes);
-        let expected = vec![
-            15, 69, 84, 72, 69, 82, 69, 85, 77, 46, 66, 76, 79, 67, 75, 83, 0, 1, 2, 3, 4,
-        ];
-        assert_eq!(actual, expected);
-    }
+    type HyperKZGCommitment =
+        <HyperKZGCommitmentEvaluationProof as CommitmentEvaluationProof>::Commitment;
 
-    #[test]
+    /// Table commitments taken from test_verify_attestations_with_hyper_kzg
     #[cfg(feature = "hyperkzg")]
-    fn test_extract_query_commitments_from_table_commitments_with_proof() {
-        // Use the table_commitments_with_proof from test_verify_attestations_with_hyper_kzg
+    fn blocks_table_commitments_with_proof() -> IndexMap<String, TableCommitmentWithProof> {
         let mut table_commitments_with_proof = IndexMap::new();
         table_commitments_with_proof.insert(
             "ETHEREUM.BLOCKS".to_string(),
@@ -113,6 +184,50 @@ mod tests {
                 ],
             },
         );
+        table_commitments_with_proof
+    }
+
+    #[cfg(feature = "hyperkzg")]
+    #[test]
+    fn test_generate_commitment_leaf() {
+        let table_identifier = "ETHEREUM.BLOCKS".to_string();
+        let commitment_scheme = CommitmentScheme::HyperKzg;
+        let table_commitment_bytes = vec![1, 2, 3, 4]; // Simple test data
+        let actual =
+            generate_commitment_leaf(table_identifier, commitment_scheme, table_commitment_bytes);
+        let expected = vec![
+            15, 69, 84, 72, 69, 82, 69, 85, 77, 46, 66, 76, 79, 67, 75, 83, 0, 1, 2, 3, 4,
+        ];
+        assert_eq!(actual, expected);
+    }
+
+    #[cfg(feature = "hyperkzg")]
+    #[test]
+    fn test_commitment_leaf_hash() {
+        let response = crate::base::zk_query_models::query_results_response_from_json(
+            include_str!("../../../../test_assets/valid_gateway_response.json"),
+        )
+        .unwrap();
+        let actual = commitment_leaf_hash(
+            "ETHEREUM.BLOCKS".to_string(),
+            CommitmentScheme::HyperKzg,
+            response.commitments.commitments["ETHEREUM.BLOCKS"]
+                .commitment
+                .clone(),
+        )
+        .unwrap();
+        // Keccak-256 of the leaf built from the fixture's ETHEREUM.BLOCKS commitment
+        assert_eq!(
+            hex::decode(actual.trim_start_matches("0x")).unwrap(),
+            hex::decode("2c9cf07166712e8bb5ad97caea0ff723a6d084d94870193a4e9b8862d6d603fc")
+                .unwrap()
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "hyperkzg")]
+    fn test_extract_query_commitments_from_table_commitments_with_proof() {
+        let table_commitments_with_proof = blocks_table_commitments_with_proof();
 
         // Extract QueryCommitments using HyperKZG
         let result = extract_query_commitments_from_table_commitments_with_proof::<
@@ -188,4 +303,91 @@ mod tests {
             "Column count mismatch"
         );
     }
+
+    /// The `ETHEREUM.BLOCKS` commitment from [`blocks_table_commitments_with_proof`], and the
+    /// differing one for a later block from the gateway response fixture.
+    #[cfg(feature = "hyperkzg")]
+    fn blocks_commitments() -> (
+        TableCommitment<HyperKZGCommitment>,
+        TableCommitment<HyperKZGCommitment>,
+    ) {
+        let blocks = TableRef::try_from("ETHEREUM.BLOCKS").unwrap();
+        let earlier = extract_query_commitments_from_table_commitments_with_proof::<
+            HyperKZGCommitmentEvaluationProof,
+        >(blocks_table_commitments_with_proof())
+        .unwrap()
+        .swap_remove(&blocks)
+        .unwrap();
+        let later = extract_query_commitments_from_table_commitments_with_proof::<
+            HyperKZGCommitmentEvaluationProof,
+        >(
+            crate::base::zk_query_models::query_results_response_from_json(include_str!(
+                "../../../../test_assets/valid_gateway_response.json"
+            ))
+            .unwrap()
+            .commitments
+            .commitments,
+        )
+        .unwrap()
+        .swap_remove(&blocks)
+        .unwrap();
+        (earlier, later)
+    }
+
+    #[cfg(feature = "hyperkzg")]
+    #[test]
+    fn we_can_diff_and_merge_disjoint_commitments() {
+        let blocks = TableRef::try_from("ETHEREUM.BLOCKS").unwrap();
+        let transactions = TableRef::try_from("ETHEREUM.TRANSACTIONS").unwrap();
+        let (commitment, _) = blocks_commitments();
+        let a: QueryCommitments<HyperKZGCommitment> =
+            [(blocks.clone(), commitment.clone())].into_iter().collect();
+        let b: QueryCommitments<HyperKZGCommitment> =
+            [(transactions.clone(), commitment)].into_iter().collect();
+
+        assert_eq!(
+            diff_query_commitments(&a, &b),
+            vec![
+                CommitmentDiff::OnlyInFirst(blocks.clone()),
+                CommitmentDiff::OnlyInSecond(transactions.clone())
+            ]
+        );
+        let merged = merge_query_commitments(a, b).unwrap();
+        assert_eq!(
+            merged.keys().collect::<Vec<_>>(),
+            vec![&blocks, &transactions]
+        );
+    }
+
+    #[cfg(feature = "hyperkzg")]
+    #[test]
+    fn we_can_diff_and_merge_equal_overlapping_commitments() {
+        let blocks = TableRef::try_from("ETHEREUM.BLOCKS").unwrap();
+        let (commitment, _) = blocks_commitments();
+        let a: QueryCommitments<HyperKZGCommitment> =
+            [(blocks.clone(), commitment)].into_iter().collect();
+
+        assert!(diff_query_commitments(&a, &a.clone()).is_empty());
+        assert_eq!(merge_query_commitments(a.clone(), a.clone()).unwrap(), a);
+    }
+
+    #[cfg(feature = "hyperkzg")]
+    #[test]
+    fn we_cannot_merge_conflicting_commitments() {
+        let blocks = TableRef::try_from("ETHEREUM.BLOCKS").unwrap();
+        let (earlier, later) = blocks_commitments();
+        let a: QueryCommitments<HyperKZGCommitment> =
+            [(blocks.clone(), earlier)].into_iter().collect();
+        let b: QueryCommitments<HyperKZGCommitment> =
+            [(blocks.clone(), later)].into_iter().collect();
+
+        assert_eq!(
+            diff_query_commitments(&a, &b),
+            vec![CommitmentDiff::Differs(blocks.clone())]
+        );
+        assert_eq!(
+            merge_query_commitments(a, b),
+            Err(ConflictingCommitmentsError { table: blocks })
+        );
+    }
 }
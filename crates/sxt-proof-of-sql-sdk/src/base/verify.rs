@@ -1,32 +1,37 @@
-use super::{uppercase_accessor::UppercaseAccessor, CommitmentEvaluationProofId};
+use super::{
+    canonicalize_table_ref, prover, uppercase_accessor::UppercaseAccessor,
+    CommitmentEvaluationProofId, CommitmentScheme, UnsupportedCommitmentSchemeError,
+};
 use crate::base::{
     attestation::verify_attestations,
     verifiable_commitment::extract_query_commitments_from_table_commitments_with_proof,
-    zk_query_models::QueryResultsResponse,
+    zk_query_models::{AttestedCommitments, QueryResultsResponse, TableCommitmentWithProof},
 };
 #[cfg(feature = "hyperkzg")]
 use crate::base::{
     javascript_friendly_types::{
         try_convert_table_to_javascript_friendly_table, Failure, JSFriendlyColumn,
+        JsonResultOptions,
     },
     serde::javascript_serializations::{
         deserialize_attestors_from_javascript, deserialize_query_results_from_javascript,
         deserialize_verifier_key,
     },
 };
-#[cfg(feature = "hyperkzg")]
 use indexmap::IndexMap;
 #[cfg(feature = "hyperkzg")]
 use proof_of_sql::proof_primitive::hyperkzg::HyperKZGCommitmentEvaluationProof;
 use proof_of_sql::{
     base::{
-        commitment::CommitmentEvaluationProof,
-        database::{CommitmentAccessor, LiteralValue, OwnedTable},
+        commitment::{CommitmentEvaluationProof, QueryCommitments},
+        database::{CommitmentAccessor, LiteralValue, OwnedTable, TableRef},
+        scalar::Scalar,
         try_standard_binary_deserialization,
     },
     sql::{
         evm_proof_plan::EVMProofPlan,
-        proof::{QueryError, QueryProof},
+        proof::{ProofPlan, QueryError, QueryProof},
+        proof_plans::DynProofPlan,
     },
 };
 use snafu::Snafu;
@@ -43,6 +48,31 @@ pub enum VerifyProverResponseError {
         context(false)
     )]
     Verification { source: QueryError },
+    /// A table referenced by the proof plan has no attested commitment at the attested block.
+    #[snafu(display(
+        "table {table} is not indexed for commitment scheme {scheme} at attested block {block_number}"
+    ))]
+    CommitmentNotFound {
+        table: TableRef,
+        scheme: CommitmentScheme,
+        block_number: u64,
+    },
+    /// The proof plan returned by the ZK Query API differs from the plan produced locally.
+    #[snafu(display("the proof plan returned by the ZK Query API does not match the query"))]
+    PlanMismatch,
+    /// The ZK Query API ran the query with a different commitment scheme than requested.
+    #[snafu(display(
+        "expected a response for commitment scheme {expected}, but the query was run with {actual:?}"
+    ))]
+    SchemeMismatch {
+        expected: CommitmentScheme,
+        actual: prover::CommitmentScheme,
+    },
+    /// The ZK Query API ran the query with a commitment scheme this SDK cannot verify, e.g. IPA.
+    #[snafu(display("{source}"), context(false))]
+    UnsupportedCommitmentScheme {
+        source: UnsupportedCommitmentSchemeError,
+    },
 }
 
 impl From<bincode::error::DecodeError> for VerifyProverResponseError {
@@ -51,6 +81,23 @@ impl From<bincode::error::DecodeError> for VerifyProverResponseError {
     }
 }
 
+/// Check that a ZK Query API response was produced with `CPI`'s commitment scheme, so that a
+/// mismatch is reported as such rather than as a failure to deserialize the response.
+///
+/// A scheme with no [`CommitmentScheme`] equivalent, such as IPA, which the prover falls back to
+/// when no scheme is requested, is reported as unsupported.
+fn check_commitment_scheme<CPI: CommitmentEvaluationProofId>(
+    query_results: &QueryResultsResponse,
+) -> Result<(), VerifyProverResponseError> {
+    if CommitmentScheme::try_from(query_results.commitment_scheme)? != CPI::COMMITMENT_SCHEME {
+        return Err(VerifyProverResponseError::SchemeMismatch {
+            expected: CPI::COMMITMENT_SCHEME,
+            actual: query_results.commitment_scheme,
+        });
+    }
+    Ok(())
+}
+
 /// Verify a response from the prover service (via the gateway) against the provided commitment accessor.
 pub fn verify_prover_via_gateway_response<CPI: CommitmentEvaluationProofId>(
     proof: QueryProof<CPI>,
@@ -73,10 +120,60 @@ pub fn verify_prover_via_gateway_response<CPI: CommitmentEvaluationProofId>(
     Ok(result)
 }
 
+/// Like [`verify_prover_via_gateway_response`], but takes a [`DynProofPlan`] that has not been
+/// wrapped in an [`EVMProofPlan`], e.g. one produced by local planning.
+pub fn verify_prover_via_gateway_response_dyn<CPI: CommitmentEvaluationProofId>(
+    proof: QueryProof<CPI>,
+    result: OwnedTable<<CPI as CommitmentEvaluationProof>::Scalar>,
+    proof_plan: DynProofPlan,
+    params: &[LiteralValue],
+    accessor: &impl CommitmentAccessor<<CPI as CommitmentEvaluationProof>::Commitment>,
+    verifier_setup: &<CPI as CommitmentEvaluationProof>::VerifierPublicSetup<'_>,
+) -> Result<OwnedTable<<CPI as CommitmentEvaluationProof>::Scalar>, VerifyProverResponseError> {
+    verify_prover_via_gateway_response::<CPI>(
+        proof,
+        result,
+        &EVMProofPlan::new(proof_plan),
+        params,
+        accessor,
+        verifier_setup,
+    )
+}
+
+/// Like [`verify_prover_via_gateway_response`], but also returns the result the prover claimed,
+/// whether or not it verifies.
+///
+/// The claimed result is UNTRUSTED. It is only meant for diagnosing verification failures, e.g.
+/// by comparing it with the expected result, and must never be used in place of a verified one.
+#[expect(clippy::type_complexity)]
+pub fn verify_prover_via_gateway_response_with_claimed_result<CPI: CommitmentEvaluationProofId>(
+    proof: QueryProof<CPI>,
+    result: OwnedTable<<CPI as CommitmentEvaluationProof>::Scalar>,
+    proof_plan: &EVMProofPlan,
+    params: &[LiteralValue],
+    accessor: &impl CommitmentAccessor<<CPI as CommitmentEvaluationProof>::Commitment>,
+    verifier_setup: &<CPI as CommitmentEvaluationProof>::VerifierPublicSetup<'_>,
+) -> (
+    Result<OwnedTable<<CPI as CommitmentEvaluationProof>::Scalar>, VerifyProverResponseError>,
+    OwnedTable<<CPI as CommitmentEvaluationProof>::Scalar>,
+) {
+    let claimed_result = result.clone();
+    let verified_result = verify_prover_via_gateway_response::<CPI>(
+        proof,
+        result,
+        proof_plan,
+        params,
+        accessor,
+        verifier_setup,
+    );
+    (verified_result, claimed_result)
+}
+
 #[cfg(feature = "hyperkzg")]
 fn proof_of_sql_verify_from_json_responses_as_result(
     query_results_json: String,
     valid_attestors: Vec<String>,
+    options: &JsonResultOptions,
 ) -> Result<IndexMap<String, JSFriendlyColumn>, Failure> {
     let query_results = deserialize_query_results_from_javascript(query_results_json)?;
     let valid_attestors = deserialize_attestors_from_javascript(valid_attestors)?;
@@ -88,7 +185,7 @@ fn proof_of_sql_verify_from_json_responses_as_result(
         &&verifier_setup,
     )
     .map_err(|err| Failure::VerificationError(format!("Error verifying result: {}", err)))?;
-    try_convert_table_to_javascript_friendly_table(result)
+    try_convert_table_to_javascript_friendly_table(result, options)
 }
 
 #[cfg(feature = "hyperkzg")]
@@ -96,8 +193,25 @@ pub fn proof_of_sql_verify_from_json_responses(
     query_results_json: String,
     valid_attestors: Vec<String>,
 ) -> String {
-    let result =
-        proof_of_sql_verify_from_json_responses_as_result(query_results_json, valid_attestors);
+    proof_of_sql_verify_from_json_responses_with_options(
+        query_results_json,
+        valid_attestors,
+        JsonResultOptions::default(),
+    )
+}
+
+/// Like [`proof_of_sql_verify_from_json_responses`], but with control over the result representation.
+#[cfg(feature = "hyperkzg")]
+pub fn proof_of_sql_verify_from_json_responses_with_options(
+    query_results_json: String,
+    valid_attestors: Vec<String>,
+    options: JsonResultOptions,
+) -> String {
+    let result = proof_of_sql_verify_from_json_responses_as_result(
+        query_results_json,
+        valid_attestors,
+        &options,
+    );
     crate::base::serde::javascript_serializations::serialize_javascript_friendly_type(result.into())
 }
 
@@ -106,18 +220,172 @@ pub fn verify_from_zk_query_and_substrate_responses<CPI: CommitmentEvaluationPro
     required_attestors: Vec<[u8; 20]>,
     verifier_setup: &<CPI as CommitmentEvaluationProof>::VerifierPublicSetup<'_>,
 ) -> Result<OwnedTable<<CPI as CommitmentEvaluationProof>::Scalar>, Box<dyn core::error::Error>> {
+    verify_from_zk_query_and_substrate_responses_with_params::<CPI>(
+        query_results,
+        required_attestors,
+        &[],
+        verifier_setup,
+    )
+}
+
+/// Like [`verify_from_zk_query_and_substrate_responses`], for a query run with parameters.
+pub fn verify_from_zk_query_and_substrate_responses_with_params<
+    CPI: CommitmentEvaluationProofId,
+>(
+    query_results: QueryResultsResponse,
+    required_attestors: Vec<[u8; 20]>,
+    params: &[LiteralValue],
+    verifier_setup: &<CPI as CommitmentEvaluationProof>::VerifierPublicSetup<'_>,
+) -> Result<OwnedTable<<CPI as CommitmentEvaluationProof>::Scalar>, Box<dyn core::error::Error>> {
+    let plan_bytes = query_results.plan.clone();
+    verify_from_zk_query_and_substrate_responses_with_plan::<CPI>(
+        query_results,
+        required_attestors,
+        params,
+        verifier_setup,
+        |_| Ok(try_standard_binary_deserialization(&plan_bytes)?.0),
+    )
+}
+
+/// A verified query result along with statistics about it and the response it was verified from.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VerifiedResult<S: Scalar> {
+    /// The verified result table
+    pub table: OwnedTable<S>,
+    /// Number of rows in the result
+    pub num_rows: usize,
+    /// Number of columns in the result
+    pub num_columns: usize,
+    /// Size of the serialized proof in bytes
+    pub proof_bytes_len: usize,
+    /// Size of the serialized result in bytes
+    pub result_bytes_len: usize,
+}
+
+/// Like [`verify_from_zk_query_and_substrate_responses_with_params`], but also reports the size
+/// of the result and of the response it was verified from.
+pub fn verify_from_zk_query_and_substrate_responses_detailed<CPI: CommitmentEvaluationProofId>(
+    query_results: QueryResultsResponse,
+    required_attestors: Vec<[u8; 20]>,
+    params: &[LiteralValue],
+    verifier_setup: &<CPI as CommitmentEvaluationProof>::VerifierPublicSetup<'_>,
+) -> Result<VerifiedResult<<CPI as CommitmentEvaluationProof>::Scalar>, Box<dyn core::error::Error>>
+{
+    let proof_bytes_len = query_results.proof.len();
+    let result_bytes_len = query_results.results.len();
+    let table = verify_from_zk_query_and_substrate_responses_with_params::<CPI>(
+        query_results,
+        required_attestors,
+        params,
+        verifier_setup,
+    )?;
+    Ok(VerifiedResult {
+        num_rows: table.num_rows(),
+        num_columns: table.num_columns(),
+        table,
+        proof_bytes_len,
+        result_bytes_len,
+    })
+}
+
+/// Verify the attestations of `attested_commitments` for `CPI`'s commitment scheme and return the
+/// verified commitments as [`QueryCommitments`].
+///
+/// This combines [`verify_attestations`] and
+/// [`extract_query_commitments_from_table_commitments_with_proof`]. Attestation failures are
+/// returned as [`AttestationError`](crate::base::attestation::AttestationError)s.
+pub fn verify_attestations_and_extract<CPI: CommitmentEvaluationProofId>(
+    attested_commitments: &AttestedCommitments,
+    required_attestors: Vec<[u8; 20]>,
+) -> Result<
+    QueryCommitments<<CPI as CommitmentEvaluationProof>::Commitment>,
+    Box<dyn core::error::Error>,
+> {
+    let table_commitment_with_proof = verify_attestations(
+        attested_commitments,
+        required_attestors,
+        CPI::COMMITMENT_SCHEME,
+    )?;
+    extract_query_commitments_from_table_commitments_with_proof::<CPI>(table_commitment_with_proof)
+}
+
+/// Like [`verify_from_zk_query_and_substrate_responses_with_params`], but verifies against the
+/// plan returned by `produce_plan` rather than the plan in the response.
+///
+/// `produce_plan` is given the attested commitments, so the plan can be built locally instead of
+/// trusting the ZK Query API.
+pub fn verify_from_zk_query_and_substrate_responses_with_plan<CPI: CommitmentEvaluationProofId>(
+    query_results: QueryResultsResponse,
+    required_attestors: Vec<[u8; 20]>,
+    params: &[LiteralValue],
+    verifier_setup: &<CPI as CommitmentEvaluationProof>::VerifierPublicSetup<'_>,
+    produce_plan: impl FnOnce(
+        &QueryCommitments<<CPI as CommitmentEvaluationProof>::Commitment>,
+    ) -> Result<EVMProofPlan, Box<dyn core::error::Error>>,
+) -> Result<OwnedTable<<CPI as CommitmentEvaluationProof>::Scalar>, Box<dyn core::error::Error>> {
+    check_commitment_scheme::<CPI>(&query_results)?;
     let table_commitment_with_proof = verify_attestations(
         &query_results.commitments,
         required_attestors,
         CPI::COMMITMENT_SCHEME,
+    )?;
+    verify_against_table_commitments::<CPI>(
+        &query_results,
+        table_commitment_with_proof,
+        params,
+        verifier_setup,
+        produce_plan,
+    )
+}
+
+/// Like [`verify_from_zk_query_and_substrate_responses_with_params`], but trusts the commitments in
+/// the response without verifying their attestations.
+///
+/// This saves the attestation checks, but the result is only proven correct against whatever
+/// commitments the ZK Query API returned. Only use it if those commitments come from a source
+/// that is already trusted.
+pub fn verify_from_zk_query_response_without_attestations<CPI: CommitmentEvaluationProofId>(
+    query_results: QueryResultsResponse,
+    params: &[LiteralValue],
+    verifier_setup: &<CPI as CommitmentEvaluationProof>::VerifierPublicSetup<'_>,
+) -> Result<OwnedTable<<CPI as CommitmentEvaluationProof>::Scalar>, Box<dyn core::error::Error>> {
+    check_commitment_scheme::<CPI>(&query_results)?;
+    verify_against_table_commitments::<CPI>(
+        &query_results,
+        query_results.commitments.commitments.clone(),
+        params,
+        verifier_setup,
+        |_| Ok(try_standard_binary_deserialization(&query_results.plan)?.0),
     )
-    .map_err(|err| err.to_string())?;
+}
 
+/// Verify the proof and result in `query_results` against the given table commitments.
+fn verify_against_table_commitments<CPI: CommitmentEvaluationProofId>(
+    query_results: &QueryResultsResponse,
+    table_commitment_with_proof: IndexMap<String, TableCommitmentWithProof>,
+    params: &[LiteralValue],
+    verifier_setup: &<CPI as CommitmentEvaluationProof>::VerifierPublicSetup<'_>,
+    produce_plan: impl FnOnce(
+        &QueryCommitments<<CPI as CommitmentEvaluationProof>::Commitment>,
+    ) -> Result<EVMProofPlan, Box<dyn core::error::Error>>,
+) -> Result<OwnedTable<<CPI as CommitmentEvaluationProof>::Scalar>, Box<dyn core::error::Error>> {
     let query_commitments = extract_query_commitments_from_table_commitments_with_proof::<CPI>(
         table_commitment_with_proof,
     )?;
+    let plan = produce_plan(&query_commitments)?;
+    if let Some(table) = plan
+        .get_table_references()
+        .iter()
+        .map(canonicalize_table_ref)
+        .find(|table_ref| !query_commitments.contains_key(table_ref))
+    {
+        return Err(Box::new(VerifyProverResponseError::CommitmentNotFound {
+            table,
+            scheme: CPI::COMMITMENT_SCHEME,
+            block_number: query_results.commitments.block_number,
+        }));
+    }
     let uppercased_query_commitments = UppercaseAccessor(&query_commitments);
-    let plan: EVMProofPlan = try_standard_binary_deserialization(&query_results.plan)?.0;
     let proof: QueryProof<CPI> = try_standard_binary_deserialization(&query_results.proof)?.0;
     let result: OwnedTable<<CPI as CommitmentEvaluationProof>::Scalar> =
         try_standard_binary_deserialization(&query_results.results)?.0;
@@ -126,20 +394,344 @@ pub fn verify_from_zk_query_and_substrate_responses<CPI: CommitmentEvaluationPro
         proof,
         result,
         &plan,
-        &[],
+        params,
         &uppercased_query_commitments,
         verifier_setup,
-    )
-    .map_err(|err| err.to_string())?)
+    )?)
 }
 
 #[cfg(test)]
 #[cfg(feature = "hyperkzg")]
 mod tests {
-    use crate::base::proof_of_sql_verify_from_json_responses;
+    use super::*;
+    use crate::base::{
+        proof_of_sql_verify_from_json_responses,
+        serde::javascript_serializations::deserialize_verifier_key,
+    };
+    use proof_of_sql::base::database::OwnedColumn;
     const VALID_GATEWAY_RESPONSE: &str =
         include_str!("../../../../test_assets/valid_gateway_response.json");
 
+    #[test]
+    fn we_get_a_typed_error_when_a_planned_table_has_no_commitment() {
+        let mut query_results: QueryResultsResponse =
+            serde_json::from_str(VALID_GATEWAY_RESPONSE).unwrap();
+        query_results
+            .commitments
+            .commitments
+            .shift_remove("ETHEREUM.BLOCKS")
+            .unwrap();
+
+        let err =
+            verify_from_zk_query_and_substrate_responses::<HyperKZGCommitmentEvaluationProof>(
+                query_results,
+                vec![],
+                &&deserialize_verifier_key(),
+            )
+            .unwrap_err();
+        let err = err.downcast_ref::<VerifyProverResponseError>().unwrap();
+        assert!(matches!(
+            err,
+            VerifyProverResponseError::CommitmentNotFound {
+                table,
+                scheme: CommitmentScheme::HyperKzg,
+                block_number: 9083634,
+            } if *table == "ETHEREUM.BLOCKS".parse::<TableRef>().unwrap()
+        ));
+        assert_eq!(
+            err.to_string(),
+            "table ETHEREUM.BLOCKS is not indexed for commitment scheme HyperKzg at attested block 9083634"
+        );
+    }
+
+    #[test]
+    fn we_get_a_typed_error_when_the_response_is_for_another_commitment_scheme() {
+        let mut query_results: QueryResultsResponse =
+            serde_json::from_str(VALID_GATEWAY_RESPONSE).unwrap();
+        query_results.commitment_scheme = prover::CommitmentScheme::DynamicDory;
+
+        let err =
+            verify_from_zk_query_and_substrate_responses::<HyperKZGCommitmentEvaluationProof>(
+                query_results,
+                vec![],
+                &&deserialize_verifier_key(),
+            )
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<VerifyProverResponseError>(),
+            Some(VerifyProverResponseError::SchemeMismatch {
+                expected: CommitmentScheme::HyperKzg,
+                actual: prover::CommitmentScheme::DynamicDory,
+            })
+        ));
+        assert_eq!(
+            err.to_string(),
+            "expected a response for commitment scheme HyperKzg, but the query was run with DynamicDory"
+        );
+    }
+
+    #[test]
+    fn we_can_verify_attestations_and_extract_the_commitments() {
+        let query_results: QueryResultsResponse =
+            serde_json::from_str(VALID_GATEWAY_RESPONSE).unwrap();
+
+        let query_commitments =
+            verify_attestations_and_extract::<HyperKZGCommitmentEvaluationProof>(
+                &query_results.commitments,
+                vec![],
+            )
+            .unwrap();
+        assert_eq!(
+            query_commitments,
+            extract_query_commitments_from_table_commitments_with_proof::<
+                HyperKZGCommitmentEvaluationProof,
+            >(query_results.commitments.commitments.clone())
+            .unwrap()
+        );
+
+        let mut tampered = query_results.commitments;
+        tampered.address20s[0][0] ^= 1;
+        let err =
+            verify_attestations_and_extract::<HyperKZGCommitmentEvaluationProof>(&tampered, vec![])
+                .unwrap_err();
+        assert!(err.is::<crate::base::attestation::AttestationError>());
+    }
+
+    #[test]
+    fn we_reject_a_response_proven_with_ipa() {
+        let mut query_results: QueryResultsResponse =
+            serde_json::from_str(VALID_GATEWAY_RESPONSE).unwrap();
+        query_results.commitment_scheme = prover::CommitmentScheme::Ipa;
+
+        let err =
+            verify_from_zk_query_and_substrate_responses::<HyperKZGCommitmentEvaluationProof>(
+                query_results,
+                vec![],
+                &&deserialize_verifier_key(),
+            )
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<VerifyProverResponseError>(),
+            Some(VerifyProverResponseError::UnsupportedCommitmentScheme { .. })
+        ));
+        assert_eq!(
+            err.to_string(),
+            "commitment scheme Ipa is not supported by this SDK"
+        );
+    }
+
+    #[test]
+    fn we_can_get_statistics_about_a_verified_result() {
+        let verified = verify_from_zk_query_and_substrate_responses_detailed::<
+            HyperKZGCommitmentEvaluationProof,
+        >(
+            serde_json::from_str(VALID_GATEWAY_RESPONSE).unwrap(),
+            vec![],
+            &[],
+            &&deserialize_verifier_key(),
+        )
+        .unwrap();
+
+        assert_eq!(verified.num_rows, 1);
+        assert_eq!(verified.num_columns, 2);
+        assert_eq!(verified.proof_bytes_len, 9608);
+        assert_eq!(verified.result_bytes_len, 90);
+        assert_eq!(
+            verified.table,
+            verify_from_zk_query_and_substrate_responses::<HyperKZGCommitmentEvaluationProof>(
+                serde_json::from_str(VALID_GATEWAY_RESPONSE).unwrap(),
+                vec![],
+                &&deserialize_verifier_key(),
+            )
+            .unwrap()
+        );
+    }
+
+    #[test]
+    fn we_get_the_same_outcome_verifying_with_a_dyn_or_evm_plan() {
+        let query_results: QueryResultsResponse =
+            serde_json::from_str(VALID_GATEWAY_RESPONSE).unwrap();
+        let plan: EVMProofPlan = try_standard_binary_deserialization(&query_results.plan)
+            .unwrap()
+            .0;
+        let query_commitments = extract_query_commitments_from_table_commitments_with_proof::<
+            HyperKZGCommitmentEvaluationProof,
+        >(query_results.commitments.commitments.clone())
+        .unwrap();
+        let proof = || -> QueryProof<HyperKZGCommitmentEvaluationProof> {
+            try_standard_binary_deserialization(&query_results.proof)
+                .unwrap()
+                .0
+        };
+        let result: OwnedTable<_> = try_standard_binary_deserialization(&query_results.results)
+            .unwrap()
+            .0;
+        let verifier_setup = deserialize_verifier_key();
+
+        let evm_result = verify_prover_via_gateway_response::<HyperKZGCommitmentEvaluationProof>(
+            proof(),
+            result.clone(),
+            &plan,
+            &[],
+            &query_commitments,
+            &&verifier_setup,
+        )
+        .unwrap();
+        let dyn_result =
+            verify_prover_via_gateway_response_dyn::<HyperKZGCommitmentEvaluationProof>(
+                proof(),
+                result.clone(),
+                plan.inner().clone(),
+                &[],
+                &query_commitments,
+                &&verifier_setup,
+            )
+            .unwrap();
+        assert_eq!(dyn_result, evm_result);
+
+        // A result that does not match the proof is rejected through either entry point
+        let mut tampered = result.into_inner();
+        tampered[0] = match &tampered[0] {
+            OwnedColumn::BigInt(values) => {
+                OwnedColumn::BigInt(values.iter().map(|value| value + 1).collect())
+            }
+            column => panic!("expected a BigInt column, got {column:?}"),
+        };
+        let tampered = OwnedTable::try_new(tampered).unwrap();
+        assert!(
+            verify_prover_via_gateway_response::<HyperKZGCommitmentEvaluationProof>(
+                proof(),
+                tampered.clone(),
+                &plan,
+                &[],
+                &query_commitments,
+                &&verifier_setup,
+            )
+            .is_err()
+        );
+        assert!(
+            verify_prover_via_gateway_response_dyn::<HyperKZGCommitmentEvaluationProof>(
+                proof(),
+                tampered,
+                plan.inner().clone(),
+                &[],
+                &query_commitments,
+                &&verifier_setup,
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn we_get_the_claimed_result_even_if_verification_fails() {
+        let query_results: QueryResultsResponse =
+            serde_json::from_str(VALID_GATEWAY_RESPONSE).unwrap();
+        let plan: EVMProofPlan = try_standard_binary_deserialization(&query_results.plan)
+            .unwrap()
+            .0;
+        let query_commitments = extract_query_commitments_from_table_commitments_with_proof::<
+            HyperKZGCommitmentEvaluationProof,
+        >(query_results.commitments.commitments.clone())
+        .unwrap();
+        let proof = || -> QueryProof<HyperKZGCommitmentEvaluationProof> {
+            try_standard_binary_deserialization(&query_results.proof)
+                .unwrap()
+                .0
+        };
+        let result: OwnedTable<_> = try_standard_binary_deserialization(&query_results.results)
+            .unwrap()
+            .0;
+        let verifier_setup = deserialize_verifier_key();
+
+        let (verified, claimed) = verify_prover_via_gateway_response_with_claimed_result::<
+            HyperKZGCommitmentEvaluationProof,
+        >(
+            proof(),
+            result.clone(),
+            &plan,
+            &[],
+            &query_commitments,
+            &&verifier_setup,
+        );
+        assert_eq!(verified.unwrap(), result);
+        assert_eq!(claimed, result);
+
+        let mut tampered = result.into_inner();
+        tampered[0] = match &tampered[0] {
+            OwnedColumn::BigInt(values) => {
+                OwnedColumn::BigInt(values.iter().map(|value| value + 1).collect())
+            }
+            column => panic!("expected a BigInt column, got {column:?}"),
+        };
+        let tampered = OwnedTable::try_new(tampered).unwrap();
+        let (verified, claimed) = verify_prover_via_gateway_response_with_claimed_result::<
+            HyperKZGCommitmentEvaluationProof,
+        >(
+            proof(),
+            tampered.clone(),
+            &plan,
+            &[],
+            &query_commitments,
+            &&verifier_setup,
+        );
+        assert!(matches!(
+            verified,
+            Err(VerifyProverResponseError::Verification { .. })
+        ));
+        assert_eq!(claimed, tampered);
+    }
+
+    #[test]
+    fn we_can_verify_against_a_supplied_plan() {
+        let query_results: QueryResultsResponse =
+            serde_json::from_str(VALID_GATEWAY_RESPONSE).unwrap();
+        let response_plan: EVMProofPlan = try_standard_binary_deserialization(&query_results.plan)
+            .unwrap()
+            .0;
+
+        let result = verify_from_zk_query_and_substrate_responses_with_plan::<
+            HyperKZGCommitmentEvaluationProof,
+        >(
+            query_results,
+            vec![],
+            &[],
+            &&deserialize_verifier_key(),
+            |query_commitments| {
+                assert!(
+                    query_commitments.contains_key(&"ETHEREUM.BLOCKS".parse::<TableRef>().unwrap())
+                );
+                Ok(response_plan)
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            result,
+            verify_from_zk_query_and_substrate_responses::<HyperKZGCommitmentEvaluationProof>(
+                serde_json::from_str(VALID_GATEWAY_RESPONSE).unwrap(),
+                vec![],
+                &&deserialize_verifier_key(),
+            )
+            .unwrap()
+        );
+    }
+
+    #[test]
+    fn we_cannot_verify_if_the_supplied_plan_cannot_be_produced() {
+        let query_results: QueryResultsResponse =
+            serde_json::from_str(VALID_GATEWAY_RESPONSE).unwrap();
+        let err = verify_from_zk_query_and_substrate_responses_with_plan::<
+            HyperKZGCommitmentEvaluationProof,
+        >(
+            query_results,
+            vec![],
+            &[],
+            &&deserialize_verifier_key(),
+            |_| Err("unable to plan".into()),
+        )
+        .unwrap_err();
+        assert_eq!(err.to_string(), "unable to plan");
+    }
+
     #[test]
     fn we_can_verify_using_json_inputs() {
         let res = proof_of_sql_verify_from_json_responses(
@@ -175,3 +767,124 @@ mod tests {
         assert_eq!(res, expected_response);
     }
 }
+
+/// The gateway fixture only carries a HyperKZG proof, so the Dory counterpart of
+/// `we_get_the_same_outcome_verifying_with_a_dyn_or_evm_plan` proves a small table locally.
+#[cfg(test)]
+#[cfg(all(feature = "native", feature = "trustless-planning"))]
+mod dory_tests {
+    use super::*;
+    use crate::{base::parse_single_statement, trustless_planning::produce_plan_trustlessly};
+    use ark_std::test_rng;
+    use bumpalo::Bump;
+    use proof_of_sql::{
+        base::{
+            commitment::QueryCommitmentsExt,
+            database::{
+                table_utility::{borrowed_bigint, table},
+                ColumnRef, ColumnType, OwnedColumn, TableTestAccessor,
+            },
+        },
+        proof_primitive::dory::{
+            DoryScalar, DynamicDoryEvaluationProof, ProverSetup, PublicParameters, VerifierSetup,
+        },
+        sql::proof::VerifiableQueryResult,
+    };
+
+    #[test]
+    fn we_get_the_same_outcome_verifying_a_dory_proof_with_a_dyn_or_evm_plan() {
+        let alloc = Bump::new();
+        let table_ref = TableRef::from_names(None, "TAB");
+        let public_parameters = PublicParameters::test_rand(5, &mut test_rng());
+        let prover_setup = ProverSetup::from(&public_parameters);
+        let verifier_setup = VerifierSetup::from(&public_parameters);
+        let accessor = TableTestAccessor::<DynamicDoryEvaluationProof>::new_from_table(
+            table_ref.clone(),
+            table::<DoryScalar>(vec![
+                borrowed_bigint("A", [1, 2, 3, 4], &alloc),
+                borrowed_bigint("B", [5, 6, 7, 8], &alloc),
+            ]),
+            0,
+            &prover_setup,
+        );
+        let query_commitments = QueryCommitments::from_accessor_with_max_bounds(
+            vec![
+                ColumnRef::new(table_ref.clone(), "A".into(), ColumnType::BigInt),
+                ColumnRef::new(table_ref.clone(), "B".into(), ColumnType::BigInt),
+            ],
+            &accessor,
+        );
+        let plan = produce_plan_trustlessly::<DynamicDoryEvaluationProof>(
+            &parse_single_statement("SELECT a FROM tab WHERE b > 5").unwrap(),
+            &query_commitments,
+        )
+        .unwrap();
+        let evm_plan = EVMProofPlan::new(plan.clone());
+        let prove = || {
+            VerifiableQueryResult::<DynamicDoryEvaluationProof>::new(
+                &evm_plan,
+                &accessor,
+                &&prover_setup,
+                &[],
+            )
+            .unwrap()
+        };
+        let result = prove().result;
+
+        let evm_result = verify_prover_via_gateway_response::<DynamicDoryEvaluationProof>(
+            prove().proof,
+            result.clone(),
+            &evm_plan,
+            &[],
+            &query_commitments,
+            &&verifier_setup,
+        )
+        .unwrap();
+        let dyn_result = verify_prover_via_gateway_response_dyn::<DynamicDoryEvaluationProof>(
+            prove().proof,
+            result.clone(),
+            plan.clone(),
+            &[],
+            &query_commitments,
+            &&verifier_setup,
+        )
+        .unwrap();
+        assert_eq!(dyn_result, evm_result);
+        assert_eq!(
+            evm_result.into_inner()[0],
+            OwnedColumn::BigInt(vec![2, 3, 4])
+        );
+
+        // A result that does not match the proof is rejected through either entry point
+        let mut tampered = result.into_inner();
+        tampered[0] = match &tampered[0] {
+            OwnedColumn::BigInt(values) => {
+                OwnedColumn::BigInt(values.iter().map(|value| value + 1).collect())
+            }
+            column => panic!("expected a BigInt column, got {column:?}"),
+        };
+        let tampered = OwnedTable::try_new(tampered).unwrap();
+        assert!(
+            verify_prover_via_gateway_response::<DynamicDoryEvaluationProof>(
+                prove().proof,
+                tampered.clone(),
+                &evm_plan,
+                &[],
+                &query_commitments,
+                &&verifier_setup,
+            )
+            .is_err()
+        );
+        assert!(
+            verify_prover_via_gateway_response_dyn::<DynamicDoryEvaluationProof>(
+                prove().proof,
+                tampered,
+                plan,
+                &[],
+                &query_commitments,
+                &&verifier_setup,
+            )
+            .is_err()
+        );
+    }
+}
@@ -1,4 +1,6 @@
 //! Models for ZK Query API requests and responses
+#[cfg(feature = "native")]
+use crate::base::serde::hex::{from_hex, to_hex};
 use crate::base::{
     prover::CommitmentScheme,
     serde::hex::{
@@ -7,23 +9,75 @@ use crate::base::{
         serialize_bytes_hex,
     },
 };
+use core::marker::PhantomData;
 use indexmap::IndexMap;
-use serde::{Deserialize, Serialize};
+#[cfg(feature = "native")]
+use proof_of_sql::base::{database::LiteralValue, try_standard_binary_serialization};
+use proof_of_sql::base::{
+    database::OwnedColumn, scalar::Scalar, try_standard_binary_deserialization,
+};
+use serde::{
+    de::{MapAccess, Visitor},
+    Deserialize, Deserializer, Serialize,
+};
+use snafu::Snafu;
+use sqlparser::ast::Ident;
 
 /// The request model for running a zk query model
 #[cfg(feature = "native")]
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct QuerySubmitRequest {
     /// The query to run
     pub sql_text: String,
     /// The source of the underlying data
     pub source_network: SxtNetwork,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub timeout: Option<i64>,
     /// The commitment scheme to use for the query
-    pub commitment_scheme: Option<CommitmentScheme>,
+    ///
+    /// This is always sent, since the prover falls back to IPA when it is absent, which no
+    /// verifier in this SDK accepts.
+    pub commitment_scheme: CommitmentScheme,
     /// The block hash of the data to use
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub block_hash: Option<String>,
+    /// The hex encoded query parameters, see [`serialize_query_params`]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub params: Option<String>,
+}
+
+/// Serializes query parameters into the hex encoding expected by [`QuerySubmitRequest::params`].
+#[cfg(feature = "native")]
+pub fn serialize_query_params(
+    params: &[LiteralValue],
+) -> Result<String, bincode::error::EncodeError> {
+    Ok(to_hex(&try_standard_binary_serialization(params)?))
+}
+
+/// Errors that can occur when decoding query parameters with [`decode_params_hex`].
+#[cfg(feature = "native")]
+#[derive(Snafu, Debug)]
+pub enum DecodeParamsError {
+    /// The parameters are not valid hex.
+    #[snafu(
+        display("query parameters are not valid hex: {source}"),
+        context(false)
+    )]
+    Hex { source: hex::FromHexError },
+    /// The decoded bytes are not serialized query parameters.
+    #[snafu(
+        display("unable to deserialize query parameters: {source}"),
+        context(false)
+    )]
+    Deserialization { source: bincode::error::DecodeError },
+}
+
+/// Decodes query parameters encoded by [`serialize_query_params`], e.g. from a captured
+/// [`QuerySubmitRequest::params`]. The `0x` prefix is optional.
+#[cfg(feature = "native")]
+pub fn decode_params_hex(hex: &str) -> Result<Vec<LiteralValue>, DecodeParamsError> {
+    Ok(try_standard_binary_deserialization(&from_hex(hex)?)?.0)
 }
 
 /// The response to the initial zk query request
@@ -39,7 +93,7 @@ pub struct QuerySubmitResponse {
 }
 
 /// The table commitments with the merkle proof of the commitments
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
 pub struct TableCommitmentWithProof {
     #[serde(
@@ -51,7 +105,7 @@ pub struct TableCommitmentWithProof {
 }
 
 /// The commitments along with the attestations for those commitments
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
 pub struct AttestedCommitments {
     pub commitments: IndexMap<String, TableCommitmentWithProof>,
@@ -87,7 +141,39 @@ pub struct AttestedCommitments {
 }
 
 /// The results of the query
-#[derive(Serialize, Deserialize, Debug)]
+///
+/// The ZK Query API returns this as a camelCase JSON object of the form:
+///
+/// ```json
+/// {
+///   "queryId": "<uuid>",
+///   "created": "<timestamp>",
+///   "commitmentScheme": "HYPER_KZG",
+///   "success": true,
+///   "canceled": false,
+///   "error": null,
+///   "completed": "<timestamp>",
+///   "plan": "0x<hex>",
+///   "proof": "0x<hex>",
+///   "results": "0x<hex>",
+///   "commitments": {
+///     "commitments": {
+///       "ETHEREUM.BLOCKS": { "commitment": "0x<hex>", "merkleProof": ["0x<hex>"] }
+///     },
+///     "r": ["0x<32 byte hex>"],
+///     "s": ["0x<32 byte hex>"],
+///     "v": [1],
+///     "stateRoot": ["0x<hex>"],
+///     "address20s": ["0x<20 byte hex>"],
+///     "blockNumber": 9083634,
+///     "blockHash": "0x<32 byte hex>"
+///   }
+/// }
+/// ```
+///
+/// Hex fields are serialized with a `0x` prefix, but are accepted with or without it. Unknown
+/// fields are ignored.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
 pub struct QueryResultsResponse {
     /// The job number corresponding to the initial query
@@ -120,6 +206,103 @@ pub struct QueryResultsResponse {
     pub results: Vec<u8>,
 }
 
+/// Errors that can occur when decoding [`QueryResultsResponse::results`].
+#[derive(Snafu, Debug)]
+pub enum DecodeResultsError {
+    /// The response uses a commitment scheme this SDK does not support.
+    #[snafu(display("{source}"), context(false))]
+    UnsupportedCommitmentScheme {
+        source: crate::base::UnsupportedCommitmentSchemeError,
+    },
+    /// The result bytes are not a serialized table.
+    #[snafu(
+        display("unable to deserialize query results: {source}"),
+        context(false)
+    )]
+    Deserialization { source: bincode::error::DecodeError },
+}
+
+impl QueryResultsResponse {
+    /// Returns the number of rows in [`Self::results`].
+    ///
+    /// Only the first column is decoded, since every column of a result has the same length, and
+    /// nothing is verified. This is meant for e.g. skipping verification of an empty result. Use
+    /// the verified table when the row count has to be trusted.
+    pub fn num_rows(&self) -> Result<usize, DecodeResultsError> {
+        Ok(
+            match crate::base::CommitmentScheme::try_from(self.commitment_scheme)? {
+                crate::base::CommitmentScheme::DynamicDory => decode_num_rows::<
+                    proof_of_sql::proof_primitive::dory::DoryScalar,
+                >(&self.results)?,
+                #[cfg(feature = "hyperkzg")]
+                crate::base::CommitmentScheme::HyperKzg => decode_num_rows::<
+                    proof_of_sql::proof_primitive::hyperkzg::BNScalar,
+                >(&self.results)?,
+            },
+        )
+    }
+
+    /// Returns whether [`Self::results`] has no rows.
+    ///
+    /// Like [`Self::num_rows`], this does not verify the results.
+    pub fn is_empty(&self) -> Result<bool, DecodeResultsError> {
+        Ok(self.num_rows()? == 0)
+    }
+}
+
+/// The row count of a serialized [`OwnedTable`](proof_of_sql::base::database::OwnedTable),
+/// decoded from its first column only.
+///
+/// A table is serialized as a map from column names to columns, so the remaining columns are
+/// simply left unread.
+struct FirstColumnLen<S>(usize, PhantomData<S>);
+
+impl<'de, S: Scalar> Deserialize<'de> for FirstColumnLen<S> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct FirstColumnVisitor<S>(PhantomData<S>);
+
+        impl<'de, S: Scalar> Visitor<'de> for FirstColumnVisitor<S> {
+            type Value = FirstColumnLen<S>;
+
+            fn expecting(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+                formatter.write_str("a map from column names to columns")
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+                let num_rows = map
+                    .next_entry::<Ident, OwnedColumn<S>>()?
+                    .map_or(0, |(_, column)| column.len());
+                Ok(FirstColumnLen(num_rows, PhantomData))
+            }
+        }
+
+        deserializer.deserialize_map(FirstColumnVisitor(PhantomData))
+    }
+}
+
+/// Decodes the row count of a result table with scalar `S`.
+fn decode_num_rows<S: Scalar>(results: &[u8]) -> Result<usize, bincode::error::DecodeError> {
+    let num_rows: FirstColumnLen<S> = try_standard_binary_deserialization(results)?.0;
+    Ok(num_rows.0)
+}
+
+/// Serializes a [`QueryResultsResponse`] to JSON in the same shape the ZK Query API returns.
+///
+/// Useful for capturing a response to disk so it can be re-verified later without network access.
+pub fn query_results_response_to_json(
+    response: &QueryResultsResponse,
+) -> Result<String, serde_json::Error> {
+    serde_json::to_string_pretty(response)
+}
+
+/// Deserializes a [`QueryResultsResponse`] from JSON, e.g. one written by
+/// [`query_results_response_to_json`] or captured from the ZK Query API.
+pub fn query_results_response_from_json(
+    json: &str,
+) -> Result<QueryResultsResponse, serde_json::Error> {
+    serde_json::from_str(json)
+}
+
 /// The status of a query
 #[cfg(feature = "native")]
 #[derive(Serialize, Deserialize, Debug)]
@@ -133,6 +316,15 @@ pub struct QueryStatusResponse {
     pub status: ZkQueryStatus,
 }
 
+/// A page of zk queries returned by the listing endpoint
+#[cfg(feature = "native")]
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct QueryListResponse {
+    /// The queries on this page, each with its current status
+    pub queries: Vec<QueryStatusResponse>,
+}
+
 /// The request model to get a proof plan
 #[cfg(feature = "native")]
 #[derive(Serialize, Deserialize, Debug)]
@@ -145,6 +337,12 @@ pub struct QueryPlanRequest {
     pub source_network: SxtNetwork,
     #[serde(default = "default_evm_compatible")]
     pub evm_compatible: bool,
+    /// The commitment scheme the plan will be proven with
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub commitment_scheme: Option<CommitmentScheme>,
+    /// The hex encoded query parameters, see [`serialize_query_params`]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub params: Option<String>,
 }
 
 #[cfg(feature = "native")]
@@ -152,6 +350,32 @@ fn default_evm_compatible() -> bool {
     true
 }
 
+#[cfg(feature = "native")]
+impl QueryPlanRequest {
+    /// Create a request for a plan to be proven with `commitment_scheme`.
+    ///
+    /// Dynamic Dory plans are requested without the EVM wrapping; any other scheme, or no
+    /// scheme, gets an EVM compatible plan.
+    ///
+    /// Every verification path of this SDK, including [`produce_plan`](crate::native::produce_plan),
+    /// deserializes the plan as an `EVMProofPlan`, so a Dynamic Dory plan from this constructor
+    /// can only be handed to another consumer. To verify a Dynamic Dory plan with this SDK, set
+    /// `evm_compatible` back to `true`.
+    pub fn new(
+        sql_text: String,
+        source_network: SxtNetwork,
+        commitment_scheme: Option<CommitmentScheme>,
+    ) -> Self {
+        Self {
+            sql_text,
+            source_network,
+            evm_compatible: commitment_scheme != Some(CommitmentScheme::DynamicDory),
+            commitment_scheme,
+            params: None,
+        }
+    }
+}
+
 /// The return model with the proof plan
 #[cfg(feature = "native")]
 #[derive(Serialize, Deserialize, Debug)]
@@ -190,3 +414,156 @@ pub enum ZkQueryStatus {
     /// The status is unkown
     Unknown,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "native")]
+    #[test]
+    fn we_can_round_trip_query_params_through_hex() {
+        let params = vec![
+            LiteralValue::BigInt(22419300),
+            LiteralValue::Boolean(true),
+            LiteralValue::VarChar("0xabc".to_string()),
+            LiteralValue::Int(-7),
+        ];
+        let hex = serialize_query_params(&params).unwrap();
+
+        assert_eq!(decode_params_hex(&hex).unwrap(), params);
+        assert_eq!(decode_params_hex(&hex[2..]).unwrap(), params);
+        assert!(matches!(
+            decode_params_hex("0xzz"),
+            Err(DecodeParamsError::Hex { .. })
+        ));
+    }
+
+    #[test]
+    fn we_can_round_trip_a_query_results_response_through_json() {
+        let response = query_results_response_from_json(include_str!(
+            "../../../../test_assets/valid_gateway_response.json"
+        ))
+        .unwrap();
+        let json = query_results_response_to_json(&response).unwrap();
+        let round_tripped = query_results_response_from_json(&json).unwrap();
+
+        assert_eq!(round_tripped, response);
+        assert_eq!(
+            query_results_response_to_json(&round_tripped).unwrap(),
+            json
+        );
+    }
+
+    #[test]
+    fn we_can_deserialize_a_query_results_response_with_unprefixed_hex() {
+        fn strip_prefix(value: &mut serde_json::Value) {
+            match value {
+                serde_json::Value::String(hex) => {
+                    if let Some(unprefixed) = hex.strip_prefix("0x") {
+                        *hex = unprefixed.to_string();
+                    }
+                }
+                serde_json::Value::Array(values) => values.iter_mut().for_each(strip_prefix),
+                // Merkle proofs are kept as strings rather than decoded, so they are left as-is
+                serde_json::Value::Object(values) => values
+                    .iter_mut()
+                    .filter(|(key, _)| *key != "merkleProof")
+                    .for_each(|(_, value)| strip_prefix(value)),
+                _ => {}
+            }
+        }
+
+        let json = include_str!("../../../../test_assets/valid_gateway_response.json");
+        let mut unprefixed: serde_json::Value = serde_json::from_str(json).unwrap();
+        strip_prefix(&mut unprefixed);
+        assert_eq!(unprefixed["proof"].as_str().unwrap().get(..2), Some("00"));
+
+        assert_eq!(
+            query_results_response_from_json(&unprefixed.to_string()).unwrap(),
+            query_results_response_from_json(json).unwrap()
+        );
+    }
+
+    #[cfg(all(feature = "native", feature = "hyperkzg"))]
+    #[test]
+    fn we_can_count_the_rows_of_unverified_results() {
+        use proof_of_sql::{base::database::OwnedTable, proof_primitive::hyperkzg::BNScalar};
+
+        let mut response = query_results_response_from_json(include_str!(
+            "../../../../test_assets/valid_gateway_response.json"
+        ))
+        .unwrap();
+        assert_eq!(response.num_rows().unwrap(), 1);
+        assert!(!response.is_empty().unwrap());
+
+        let empty = OwnedTable::<BNScalar>::try_new(
+            [(Ident::new("BLOCK_NUMBER"), OwnedColumn::BigInt(vec![]))]
+                .into_iter()
+                .collect(),
+        )
+        .unwrap();
+        response.results = try_standard_binary_serialization(empty).unwrap();
+        assert_eq!(response.num_rows().unwrap(), 0);
+        assert!(response.is_empty().unwrap());
+
+        // Columns after the first are not decoded
+        let two_columns = OwnedTable::<BNScalar>::try_new(
+            [
+                (Ident::new("A"), OwnedColumn::BigInt(vec![1, 2])),
+                (Ident::new("B"), OwnedColumn::BigInt(vec![3, 4])),
+            ]
+            .into_iter()
+            .collect(),
+        )
+        .unwrap();
+        let mut results = try_standard_binary_serialization(two_columns).unwrap();
+        results.truncate(results.len() - 8);
+        response.results = results;
+        assert_eq!(response.num_rows().unwrap(), 2);
+
+        response.results = vec![0xff; 3];
+        assert!(matches!(
+            response.num_rows(),
+            Err(DecodeResultsError::Deserialization { .. })
+        ));
+        response.commitment_scheme = CommitmentScheme::Ipa;
+        assert!(matches!(
+            response.is_empty(),
+            Err(DecodeResultsError::UnsupportedCommitmentScheme { .. })
+        ));
+    }
+
+    #[cfg(feature = "native")]
+    #[test]
+    fn we_omit_absent_optional_fields_when_serializing_a_submit_request() {
+        let request = QuerySubmitRequest {
+            sql_text: "SELECT * FROM ETHEREUM.BLOCKS".to_string(),
+            source_network: SxtNetwork::Mainnet,
+            timeout: None,
+            commitment_scheme: CommitmentScheme::DynamicDory,
+            block_hash: None,
+            params: None,
+        };
+        assert_eq!(
+            serde_json::to_value(&request).unwrap(),
+            serde_json::json!({
+                "sqlText": "SELECT * FROM ETHEREUM.BLOCKS",
+                "sourceNetwork": "mainnet",
+                "commitmentScheme": "DYNAMIC_DORY",
+            })
+        );
+
+        let request = QuerySubmitRequest {
+            timeout: Some(30),
+            commitment_scheme: CommitmentScheme::HyperKzg,
+            block_hash: Some("0x00".to_string()),
+            params: Some("0x01".to_string()),
+            ..request
+        };
+        let json = serde_json::to_value(&request).unwrap();
+        assert_eq!(json["timeout"], 30);
+        assert_eq!(json["commitmentScheme"], "HYPER_KZG");
+        assert_eq!(json["blockHash"], "0x00");
+        assert_eq!(json["params"], "0x01");
+    }
+}
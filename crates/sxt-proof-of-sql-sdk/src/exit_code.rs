@@ -0,0 +1,163 @@
+//! Process exit codes of the command line interface.
+use crate::{
+    base::{attestation::AttestationError, ParseSqlError, VerifyProverResponseError},
+    native::{SdkError, UnboundPlaceholderError, ZkQueryClientError},
+};
+
+/// Exit code of the CLI, one per class of failure, so scripts can tell failures apart.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum CliExitCode {
+    /// The command succeeded.
+    Success = 0,
+    /// The command failed for a reason not covered by another code, e.g. an unreadable file.
+    Other = 1,
+    /// The query or its parameters are invalid.
+    InvalidQuery = 2,
+    /// The auth service or the ZK Query API could not be reached, or reported a failure.
+    Network = 3,
+    /// The proof or the query result did not verify.
+    Verification = 4,
+    /// The attestations of the commitments did not verify.
+    Attestation = 5,
+}
+
+impl From<CliExitCode> for std::process::ExitCode {
+    fn from(code: CliExitCode) -> Self {
+        std::process::ExitCode::from(code as u8)
+    }
+}
+
+impl CliExitCode {
+    /// Classify an error returned by a command handler.
+    ///
+    /// The error and then each of its sources are checked in turn, and the first one that can
+    /// be classified decides the code.
+    pub fn for_error(error: &(dyn core::error::Error + 'static)) -> Self {
+        core::iter::successors(Some(error), |error| error.source())
+            .find_map(Self::classify)
+            .unwrap_or(CliExitCode::Other)
+    }
+
+    fn classify(error: &(dyn core::error::Error + 'static)) -> Option<Self> {
+        if let Some(error) = error.downcast_ref::<SdkError>() {
+            return match error {
+                SdkError::InvalidQuery { .. } | SdkError::InvalidParams { .. } => {
+                    Some(CliExitCode::InvalidQuery)
+                }
+                SdkError::Authentication { .. }
+                | SdkError::ZkQueryApi { .. }
+                | SdkError::QueryFailed { .. } => Some(CliExitCode::Network),
+                SdkError::Attestation { .. } => Some(CliExitCode::Attestation),
+                SdkError::Verification { source } if source.is::<AttestationError>() => {
+                    Some(CliExitCode::Attestation)
+                }
+                SdkError::Verification { .. } => Some(CliExitCode::Verification),
+                SdkError::VerifierSetup { .. } | SdkError::ResultConversion { .. } => {
+                    Some(CliExitCode::Other)
+                }
+            };
+        }
+        if error.is::<ParseSqlError>() || error.is::<UnboundPlaceholderError>() {
+            Some(CliExitCode::InvalidQuery)
+        } else if error.is::<reqwest::Error>() || error.is::<ZkQueryClientError>() {
+            Some(CliExitCode::Network)
+        } else if error.is::<AttestationError>() {
+            Some(CliExitCode::Attestation)
+        } else if error.is::<VerifyProverResponseError>() {
+            Some(CliExitCode::Verification)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "hyperkzg")]
+mod tests {
+    use super::*;
+    use crate::{
+        base::CommitmentScheme,
+        native::mock_server::{query_and_verify_args, tampered_fixture_file},
+        query_and_verify::{query_and_verify, QueryAndVerifySdkArgs},
+        verify_from_file_subcommand::{verify_from_file, VerifyFromFileArgs},
+    };
+    use std::path::PathBuf;
+    use url::Url;
+
+    const VALID_GATEWAY_RESPONSE_PATH: &str = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/../../test_assets/valid_gateway_response.json"
+    );
+
+    /// Arguments for a query against services that cannot be reached.
+    fn offline_args(query: &str) -> QueryAndVerifySdkArgs {
+        query_and_verify_args(&Url::parse("http://127.0.0.1:1").unwrap(), query)
+    }
+
+    fn verify_from_file_args(response: PathBuf, attestor: &str) -> VerifyFromFileArgs {
+        VerifyFromFileArgs {
+            response,
+            commitment_scheme: CommitmentScheme::HyperKzg,
+            verifier_setup: None,
+            attestors: vec![attestor.parse().unwrap()],
+        }
+    }
+
+    #[tokio::test]
+    async fn an_invalid_query_exits_with_the_invalid_query_code() {
+        let error = query_and_verify(offline_args("SELEC * FROM"))
+            .await
+            .unwrap_err();
+        assert_eq!(
+            CliExitCode::for_error(error.as_ref()),
+            CliExitCode::InvalidQuery
+        );
+    }
+
+    #[tokio::test]
+    async fn an_unreachable_service_exits_with_the_network_code() {
+        let error = query_and_verify(offline_args("SELECT * FROM ethereum.blocks"))
+            .await
+            .unwrap_err();
+        assert_eq!(CliExitCode::for_error(error.as_ref()), CliExitCode::Network);
+    }
+
+    #[test]
+    fn a_missing_attestor_exits_with_the_attestation_code() {
+        let error = verify_from_file(&verify_from_file_args(
+            VALID_GATEWAY_RESPONSE_PATH.into(),
+            "0x0000000000000000000000000000000000000001",
+        ))
+        .unwrap_err();
+        assert_eq!(
+            CliExitCode::for_error(error.as_ref()),
+            CliExitCode::Attestation
+        );
+    }
+
+    #[test]
+    fn a_tampered_result_exits_with_the_verification_code() {
+        let path = tampered_fixture_file("results");
+        let result = verify_from_file(&verify_from_file_args(
+            path.clone(),
+            "0x349b729d1cEeAAe54fAB5655F621750Be6FadB49",
+        ));
+        std::fs::remove_file(path).unwrap();
+
+        assert_eq!(
+            CliExitCode::for_error(result.unwrap_err().as_ref()),
+            CliExitCode::Verification
+        );
+    }
+
+    #[test]
+    fn an_unreadable_file_exits_with_the_other_code() {
+        let error = verify_from_file(&verify_from_file_args(
+            "/nonexistent/response.json".into(),
+            "0x349b729d1cEeAAe54fAB5655F621750Be6FadB49",
+        ))
+        .unwrap_err();
+        assert_eq!(CliExitCode::for_error(error.as_ref()), CliExitCode::Other);
+    }
+}
@@ -2,6 +2,8 @@
 pub mod args;
 pub mod base;
 #[cfg(feature = "native")]
+pub mod exit_code;
+#[cfg(feature = "native")]
 pub mod native;
 #[cfg(feature = "native")]
 pub mod produce_plan_subcommand;
@@ -9,5 +11,7 @@ pub mod produce_plan_subcommand;
 pub mod query_and_verify;
 #[cfg(feature = "trustless-planning")]
 mod trustless_planning;
-#[cfg(feature = "wasm")]
+#[cfg(feature = "native")]
+pub mod verify_from_file_subcommand;
+#[cfg(feature = "wasm-dory")]
 pub mod wasm;
@@ -1,20 +1,31 @@
 use clap::Parser;
 use dotenv::dotenv;
+use std::process::ExitCode;
 use sxt_proof_of_sql_sdk::{
     args::{ProofOfSqlSdkArgs, ProofOfSqlSdkSubcommands},
+    exit_code::CliExitCode,
     produce_plan_subcommand::produce_plan_command,
     query_and_verify::query_and_verify,
+    verify_from_file_subcommand::verify_from_file_command,
 };
 
 #[tokio::main]
-async fn main() -> Result<(), Box<dyn core::error::Error>> {
+async fn main() -> ExitCode {
     // Load environment variables from .env file, if available
     dotenv().ok();
 
     // Parse command-line arguments
     let sdk_args = ProofOfSqlSdkArgs::parse();
-    match sdk_args.command {
+    let result = match sdk_args.command {
         ProofOfSqlSdkSubcommands::QueryAndVerify(args) => query_and_verify(*args).await,
         ProofOfSqlSdkSubcommands::ProducePlan(args) => produce_plan_command(*args).await,
+        ProofOfSqlSdkSubcommands::VerifyFromFile(args) => verify_from_file_command(*args),
+    };
+    match result {
+        Ok(()) => CliExitCode::Success.into(),
+        Err(error) => {
+            eprintln!("Error: {error}");
+            CliExitCode::for_error(error.as_ref()).into()
+        }
     }
 }
@@ -1,4 +1,20 @@
 use reqwest::Client;
+use serde::Serialize;
+
+/// Scope and audience to request an access token for.
+///
+/// Some deployments mint tokens with different permissions for the ZK Query API and for other
+/// services. The default requests neither, which gives the auth service's default token.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct AuthScope {
+    /// Scope to request, e.g. the permissions needed by the ZK Query API
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scope: Option<String>,
+    /// Audience the token is intended for
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub audience: Option<String>,
+}
+
 /// Get access token from the API key
 ///
 /// To obtain an API key please visit [Space and Time Studio](https://app.spaceandtime.ai/) and create an account.
@@ -6,17 +22,73 @@ use reqwest::Client;
 pub async fn get_access_token(
     apikey: &str,
     url: &str,
+) -> Result<String, Box<dyn core::error::Error>> {
+    get_access_token_with_scope(apikey, url, &AuthScope::default()).await
+}
+
+/// Get access token from the API key, requesting the given [`AuthScope`]
+///
+/// The scope and audience are sent as a JSON body. With the default [`AuthScope`] no body is
+/// sent, exactly as in [`get_access_token`].
+pub async fn get_access_token_with_scope(
+    apikey: &str,
+    url: &str,
+    auth_scope: &AuthScope,
 ) -> Result<String, Box<dyn core::error::Error>> {
     let client = Client::new();
     let auth_url = format!("{}/auth/apikey", url);
-    let response = client
-        .post(auth_url)
-        .header("apikey", apikey)
-        .send()
-        .await?;
+    let mut request = client.post(auth_url).header("apikey", apikey);
+    if *auth_scope != AuthScope::default() {
+        request = request.json(auth_scope);
+    }
+    let response = request.send().await?;
     let response_json = response.json::<serde_json::Value>().await?;
     let access_token = response_json["accessToken"]
         .as_str()
         .ok_or("No access token")?;
     Ok(access_token.to_string())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::native::mock_server::mock_server;
+
+    fn auth_routes() -> Vec<(&'static str, String)> {
+        vec![(
+            "/proxy/auth/apikey",
+            r#"{"accessToken":"token"}"#.to_string(),
+        )]
+    }
+
+    #[tokio::test]
+    async fn we_request_a_default_token_without_a_body() {
+        let (base_url, requests) = mock_server(auth_routes());
+
+        let token = get_access_token("key", base_url.join("proxy").unwrap().as_str())
+            .await
+            .unwrap();
+
+        assert_eq!(token, "token");
+        assert_eq!(requests.lock().unwrap()[0].1, "");
+    }
+
+    #[tokio::test]
+    async fn we_forward_the_requested_scope_and_audience() {
+        let (base_url, requests) = mock_server(auth_routes());
+
+        get_access_token_with_scope(
+            "key",
+            base_url.join("proxy").unwrap().as_str(),
+            &AuthScope {
+                scope: Some("zkquery".to_string()),
+                audience: None,
+            },
+        )
+        .await
+        .unwrap();
+
+        let body: serde_json::Value = serde_json::from_str(&requests.lock().unwrap()[0].1).unwrap();
+        assert_eq!(body, serde_json::json!({ "scope": "zkquery" }));
+    }
+}
@@ -1,29 +1,216 @@
-use super::{get_access_token, ZkQueryClient};
+use super::{
+    error::{AuthenticationSnafu, QueryFailedSnafu, VerificationSnafu, ZkQueryApiSnafu},
+    get_access_token_with_scope, AuthScope, PollStrategy, ResultCache, ResultCacheKey, SdkError,
+    TableSchema, ZkQueryClient, DEFAULT_MAX_RESPONSE_BYTES,
+};
+#[cfg(feature = "hyperkzg")]
+use crate::base::{
+    javascript_friendly_types::{try_convert_table_to_javascript_friendly_table, Failure},
+    serde::javascript_serializations::serialize_javascript_friendly_type,
+    JsonResultOptions,
+};
+#[cfg(feature = "trustless-planning")]
+use crate::{
+    base::verify_from_zk_query_and_substrate_responses_with_plan,
+    trustless_planning::produce_plan_trustlessly,
+};
 use crate::{
     base::{
+        attestation::{verify_attestation_count, verify_attestations, verify_attested_block},
+        canonicalize_table_ref, parse_single_statement,
         serde::hex::to_hex,
-        verify_from_zk_query_and_substrate_responses,
-        zk_query_models::{QuerySubmitRequest, SxtNetwork},
-        CommitmentEvaluationProofId, CommitmentScheme,
+        verifiable_commitment::extract_query_commitments_from_table_commitments_with_proof,
+        verify_from_zk_query_and_substrate_responses_with_params,
+        verify_from_zk_query_response_without_attestations,
+        zk_query_models::{
+            serialize_query_params, QueryResultsResponse, QuerySubmitRequest, SxtNetwork,
+        },
+        CommitmentEvaluationProofId, CommitmentScheme, VerifyProverResponseError,
     },
     native::dyn_owned_table::DynOwnedTable,
 };
 use bumpalo::Bump;
+use datafusion::arrow::record_batch::RecordBatch;
+use futures::{stream, StreamExt};
+use indexmap::IndexMap;
 #[cfg(feature = "hyperkzg")]
 use proof_of_sql::proof_primitive::hyperkzg::HyperKZGCommitmentEvaluationProof;
+#[cfg(feature = "trustless-planning")]
+use proof_of_sql::{base::try_standard_binary_serialization, sql::evm_proof_plan::EVMProofPlan};
 use proof_of_sql::{
-    base::{commitment::CommitmentEvaluationProof, database::OwnedTable},
+    base::{
+        commitment::CommitmentEvaluationProof,
+        database::{LiteralValue, OwnedTable, TableRef},
+    },
     proof_primitive::dory::DynamicDoryEvaluationProof,
 };
-use reqwest::Client;
+use snafu::ResultExt;
+use sqlparser::ast::{
+    Expr, GroupByExpr, SelectItem, SetExpr, Statement, TableFactor, TableWithJoins,
+};
+use std::{borrow::Cow, sync::Arc, time::Duration};
+use tokio::{sync::mpsc, task::spawn_blocking};
 use url::Url;
 
+/// Number of chunks buffered by [`SxTClient::query_and_verify_stream`] before the sender waits for
+/// the consumer.
+const STREAM_CHANNEL_CAPACITY: usize = 16;
+
+/// Default minimum number of distinct signers that must attest the commitments of a result.
+pub const DEFAULT_MIN_ATTESTATIONS: usize = 2;
+
+/// Maximum number of queries of a [`SxTClient::query_and_verify_batch`] run at the same time.
+pub const MAX_BATCH_CONCURRENCY: usize = 4;
+
+/// Emit a record batch as chunks of at most `chunk_size` rows through a bounded channel.
+///
+/// An empty batch is emitted as-is so the consumer still receives the schema.
+fn stream_record_batch(batch: RecordBatch, chunk_size: usize) -> mpsc::Receiver<RecordBatch> {
+    let (sender, receiver) = mpsc::channel(STREAM_CHANNEL_CAPACITY);
+    let chunk_size = chunk_size.max(1);
+    tokio::spawn(async move {
+        if batch.num_rows() == 0 {
+            let _ = sender.send(batch).await;
+            return;
+        }
+        for offset in (0..batch.num_rows()).step_by(chunk_size) {
+            let length = chunk_size.min(batch.num_rows() - offset);
+            if sender.send(batch.slice(offset, length)).await.is_err() {
+                // The receiver was dropped, so nobody is listening anymore
+                break;
+            }
+        }
+    });
+    receiver
+}
+
+/// Run CPU-heavy work such as proof verification on tokio's blocking thread pool, so it neither
+/// stalls the async executor nor pins the calling task's worker thread.
+///
+/// Dropping or aborting the awaiting task stops waiting for `f`, although `f` itself runs to
+/// completion. A panic in `f` is resumed in the caller.
+async fn run_blocking<T: Send + 'static>(f: impl FnOnce() -> T + Send + 'static) -> T {
+    match spawn_blocking(f).await {
+        Ok(value) => value,
+        Err(error) => std::panic::resume_unwind(error.into_panic()),
+    }
+}
+
+/// Make a verification error sendable, so it can be returned from [`run_blocking`].
+///
+/// A [`VerifyProverResponseError`] keeps its type so callers can still match on it. Any other
+/// error is kept as its message.
+fn into_send_error(
+    error: Box<dyn core::error::Error>,
+) -> Box<dyn core::error::Error + Send + Sync> {
+    match error.downcast::<VerifyProverResponseError>() {
+        Ok(error) => error,
+        Err(error) => error.to_string().into(),
+    }
+}
+
+/// Verify a ZK Query API response, checking its attestations unless
+/// `skip_attestation_verification` is set.
+fn verify_query_results<CPI: CommitmentEvaluationProofId>(
+    skip_attestation_verification: bool,
+    query_results: QueryResultsResponse,
+    params: &[LiteralValue],
+    verifier_setup: &<CPI as CommitmentEvaluationProof>::VerifierPublicSetup<'_>,
+) -> Result<OwnedTable<<CPI as CommitmentEvaluationProof>::Scalar>, Box<dyn core::error::Error>> {
+    if skip_attestation_verification {
+        verify_from_zk_query_response_without_attestations::<CPI>(
+            query_results,
+            params,
+            verifier_setup,
+        )
+    } else {
+        verify_from_zk_query_and_substrate_responses_with_params::<CPI>(
+            query_results,
+            vec![],
+            params,
+            verifier_setup,
+        )
+    }
+}
+
+/// Deserialize a verifier setup that was already loaded once with
+/// [`SxTClient::load_verifier_setup`], on a blocking thread with its own allocator.
+fn deserialize_loaded_verifier_setup<'a, CPI: CommitmentEvaluationProofId>(
+    verifier_setup_bytes: &[u8],
+    bump: &'a Bump,
+) -> Result<
+    <CPI as CommitmentEvaluationProof>::VerifierPublicSetup<'a>,
+    Box<dyn core::error::Error + Send + Sync>,
+> {
+    CPI::deserialize_verifier_setup(verifier_setup_bytes, bump)
+        .map_err(|error| format!("unable to load verifier setup: {error}").into())
+}
+
+/// A verified result along with whether it looks capped by an implicit row limit.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CheckedResult {
+    /// The verified result table
+    pub table: DynOwnedTable,
+    /// Whether the result has fewer rows than the table it selects from, although the query
+    /// should return all of them. This is a heuristic, see [`SxTClient::query_and_verify_checked`].
+    pub looks_truncated: bool,
+}
+
+/// Returns `true` if a verified result looks like it was capped by an implicit row limit.
+///
+/// The ZK Query API does not report truncation, so this is a heuristic. It only applies to plain
+/// projections over a single table (no filters, grouping, aggregation or explicit limits), where
+/// every row of the table is expected in the result.
+fn result_looks_truncated(
+    query: &Statement,
+    num_result_rows: usize,
+    table_num_rows: impl Fn(&TableRef) -> Option<usize>,
+) -> bool {
+    let Statement::Query(query) = query else {
+        return false;
+    };
+    if query.limit.is_some() || query.offset.is_some() || query.fetch.is_some() {
+        return false;
+    }
+    let SetExpr::Select(select) = query.body.as_ref() else {
+        return false;
+    };
+    let [TableWithJoins {
+        relation: TableFactor::Table { name, .. },
+        joins,
+    }] = select.from.as_slice()
+    else {
+        return false;
+    };
+    let is_plain_projection = joins.is_empty()
+        && select.selection.is_none()
+        && select.having.is_none()
+        && select.distinct.is_none()
+        && matches!(&select.group_by, GroupByExpr::Expressions(exprs) if exprs.is_empty())
+        && select.projection.iter().all(|item| match item {
+            SelectItem::UnnamedExpr(expr) | SelectItem::ExprWithAlias { expr, .. } => {
+                matches!(expr, Expr::Identifier(_) | Expr::CompoundIdentifier(_))
+            }
+            SelectItem::Wildcard(_) | SelectItem::QualifiedWildcard(..) => true,
+        });
+    is_plain_projection
+        && TableRef::try_from(name.to_string().as_str())
+            .ok()
+            .and_then(|table_ref| table_num_rows(&canonicalize_table_ref(&table_ref)))
+            .is_some_and(|table_num_rows| num_result_rows < table_num_rows)
+}
+
 /// Space and Time (SxT) client
 #[derive(Debug, Clone)]
 pub struct SxTClient {
     /// SXT Network
+    ///
+    /// The SxT chain whose attestations results are verified against.
     pub network: SxtNetwork,
 
+    /// The network the queried data lives on, sent with every query. Defaults to mainnet.
+    pub source_network: SxtNetwork,
+
     /// Root URL for SXT ZK Query API services
     pub zk_query_root_url: Url,
 
@@ -36,8 +223,51 @@ pub struct SxTClient {
     /// if you do not have one.
     pub sxt_api_key: String,
 
+    /// Scope and audience requested for access tokens. Defaults to neither.
+    pub auth_scope: AuthScope,
+
+    /// Access token to use instead of requesting one from the Auth service for every query.
+    ///
+    /// This token is never refreshed: once it expires, the ZK Query API rejects every query until
+    /// another token is set.
+    pub access_token: Option<String>,
+
     /// Path to the verifier setup binary file. If `None`, the default verifier setup is used.
     pub verifier_setup: Option<String>,
+
+    /// Optional cache of verified results, shared between clones of this client.
+    ///
+    /// Only queries run against an explicit block hash are cached.
+    pub result_cache: Option<Arc<ResultCache>>,
+
+    /// Whether the trustless query methods reject a response whose proof plan differs from the
+    /// plan produced locally, rather than only verifying against the local plan.
+    #[cfg(feature = "trustless-planning")]
+    pub check_gateway_plan: bool,
+
+    /// Whether to trust the commitments returned by the ZK Query API without verifying their
+    /// attestations.
+    ///
+    /// This reduces the trust guarantees of every verified result: the proof is still checked,
+    /// but only against commitments that nobody has attested to.
+    pub skip_attestation_verification: bool,
+
+    /// How many times to resubmit a query that the ZK Query API reports as failed.
+    pub failed_query_retries: u32,
+
+    /// How long to wait between polls of the status of a running query.
+    pub poll_strategy: PollStrategy,
+
+    /// Minimum number of distinct signers that must attest the commitments of a result.
+    ///
+    /// Defaults to [`DEFAULT_MIN_ATTESTATIONS`]. Not enforced when attestation verification is
+    /// skipped.
+    pub min_attestations: usize,
+
+    /// Maximum size in bytes of a single response from the ZK Query API.
+    ///
+    /// Defaults to [`DEFAULT_MAX_RESPONSE_BYTES`].
+    pub max_response_bytes: usize,
 }
 
 impl SxTClient {
@@ -51,62 +281,293 @@ impl SxTClient {
     ) -> Self {
         Self {
             network,
+            source_network: SxtNetwork::Mainnet,
             zk_query_root_url,
             auth_root_url,
             sxt_api_key,
+            auth_scope: AuthScope::default(),
+            access_token: None,
             verifier_setup,
+            result_cache: None,
+            #[cfg(feature = "trustless-planning")]
+            check_gateway_plan: false,
+            skip_attestation_verification: false,
+            failed_query_retries: 0,
+            poll_strategy: PollStrategy::default(),
+            min_attestations: DEFAULT_MIN_ATTESTATIONS,
+            max_response_bytes: DEFAULT_MAX_RESPONSE_BYTES,
         }
     }
 
-    /// Query and verify a SQL query at the given SxT block by commitment evaluation proof.
+    /// Query the data on `source_network` rather than on mainnet.
+    pub fn with_source_network(mut self, source_network: SxtNetwork) -> Self {
+        self.source_network = source_network;
+        self
+    }
+
+    /// Request access tokens for the given scope and audience.
+    pub fn with_auth_scope(mut self, auth_scope: AuthScope) -> Self {
+        self.auth_scope = auth_scope;
+        self
+    }
+
+    /// Use `access_token` for every query instead of requesting one from the Auth service.
     ///
-    /// Run a SQL query and verify the result.
+    /// The token is never refreshed, so this suits short-lived clients. Once it expires, the ZK
+    /// Query API rejects every query until another token is set.
+    pub fn with_access_token(mut self, access_token: String) -> Self {
+        self.access_token = Some(access_token);
+        self
+    }
+
+    /// Enable an in-memory cache of up to `capacity` verified results, each kept for `ttl`.
     ///
-    /// If `block_ref` is `None`, the latest block is used.
-    pub async fn query_and_verify_by_cpi<CPI>(
+    /// Results are keyed by the attestation checks they were verified with, so a result verified
+    /// with relaxed checks is never returned to a client that requires stricter ones.
+    pub fn with_result_cache(mut self, capacity: usize, ttl: Duration) -> Self {
+        self.result_cache = Some(Arc::new(ResultCache::new(capacity, ttl)));
+        self
+    }
+
+    /// Make the trustless query methods fail with [`VerifyProverResponseError::PlanMismatch`]
+    /// when the ZK Query API returns a plan for a different query.
+    #[cfg(feature = "trustless-planning")]
+    pub fn with_gateway_plan_check(mut self) -> Self {
+        self.check_gateway_plan = true;
+        self
+    }
+
+    /// Resubmit queries that the ZK Query API reports as failed, up to `retries` times.
+    ///
+    /// Failures are sometimes transient on the prover side. Canceled queries are not resubmitted.
+    pub fn with_failed_query_retries(mut self, retries: u32) -> Self {
+        self.failed_query_retries = retries;
+        self
+    }
+
+    /// Wait between polls of the status of a running query according to `poll_strategy`.
+    ///
+    /// [`PollStrategy::Fixed`] with a short interval notices fast queries finish sooner than the
+    /// default exponential backoff.
+    pub fn with_poll_strategy(mut self, poll_strategy: PollStrategy) -> Self {
+        self.poll_strategy = poll_strategy;
+        self
+    }
+
+    /// Require at least `min_attestations` distinct signers to attest the commitments of a result.
+    pub fn with_min_attestations(mut self, min_attestations: usize) -> Self {
+        self.min_attestations = min_attestations;
+        self
+    }
+
+    /// Reject responses from the ZK Query API larger than `max_response_bytes`.
+    ///
+    /// Oversized responses fail with [`ZkQueryClientError::ResponseTooLarge`](crate::native::ZkQueryClientError::ResponseTooLarge) before they are
+    /// buffered in full.
+    pub fn with_max_response_bytes(mut self, max_response_bytes: usize) -> Self {
+        self.max_response_bytes = max_response_bytes;
+        self
+    }
+
+    /// Check the attested commitments of a response against `min_attestations`.
+    fn check_attestation_count(
         &self,
-        query: &str,
-        block_ref: Option<[u8; 32]>,
-        bump: &Bump,
-    ) -> Result<OwnedTable<<CPI as CommitmentEvaluationProof>::Scalar>, Box<dyn core::error::Error>>
+        query_results: &QueryResultsResponse,
+    ) -> Result<(), SdkError> {
+        if !self.skip_attestation_verification {
+            verify_attestation_count(&query_results.commitments, self.min_attestations)?;
+        }
+        Ok(())
+    }
+
+    /// Skip verifying the attestations of the commitments returned by the ZK Query API.
+    ///
+    /// See [`SxTClient::skip_attestation_verification`] for the trust this gives up.
+    pub fn without_attestation_verification(mut self) -> Self {
+        self.skip_attestation_verification = true;
+        self
+    }
+
+    /// Load the verifier setup for `CPI`, from `verifier_setup` if set.
+    ///
+    /// Returns the setup bytes along with the deserialized setup, so the setup can be deserialized
+    /// again where the borrowed one cannot go, e.g. on a blocking thread.
+    fn load_verifier_setup<'a, CPI>(
+        &self,
+        bump: &'a Bump,
+    ) -> Result<
+        (
+            Cow<'static, [u8]>,
+            <CPI as CommitmentEvaluationProof>::VerifierPublicSetup<'a>,
+        ),
+        SdkError,
+    >
     where
         CPI: CommitmentEvaluationProofId,
         <CPI as CommitmentEvaluationProofId>::DeserializationError: 'static,
     {
-        // Load verifier setup
         let verifier_setup_bytes = match &self.verifier_setup {
-            Some(path) => &std::fs::read(path)?,
-            None => CPI::DEFAULT_VERIFIER_SETUP_BYTES,
+            Some(path) => {
+                Cow::Owned(
+                    std::fs::read(path).map_err(|source| SdkError::VerifierSetup {
+                        source: source.into(),
+                    })?,
+                )
+            }
+            None => Cow::Borrowed(CPI::DEFAULT_VERIFIER_SETUP_BYTES),
         };
-        let verifier_setup = CPI::deserialize_verifier_setup(verifier_setup_bytes, bump)?;
+        let verifier_setup =
+            CPI::deserialize_verifier_setup(&verifier_setup_bytes, bump).map_err(|source| {
+                SdkError::VerifierSetup {
+                    source: source.into(),
+                }
+            })?;
+        Ok((verifier_setup_bytes, verifier_setup))
+    }
 
-        // Run the query to get the proof plan and query results and Merkle tree
-        let access_token = get_access_token(&self.sxt_api_key, self.auth_root_url.as_str()).await?;
+    /// Run a query through the ZK Query API to get the proof plan, query results and attested
+    /// commitments.
+    async fn fetch_query_results<CPI: CommitmentEvaluationProofId>(
+        &self,
+        query: &str,
+        params: &[LiteralValue],
+        block_ref: Option<[u8; 32]>,
+    ) -> Result<QueryResultsResponse, SdkError> {
+        let access_token = match &self.access_token {
+            Some(access_token) => access_token.clone(),
+            None => get_access_token_with_scope(
+                &self.sxt_api_key,
+                self.auth_root_url.as_str(),
+                &self.auth_scope,
+            )
+            .await
+            .context(AuthenticationSnafu)?,
+        };
         let client = ZkQueryClient {
-            base_url: self.zk_query_root_url.clone(),
-            client: Client::new(),
-            access_token,
+            failed_query_retries: self.failed_query_retries,
+            poll_strategy: self.poll_strategy,
+            max_response_bytes: self.max_response_bytes,
+            ..ZkQueryClient::new(self.zk_query_root_url.clone(), access_token)
         };
         let scheme = crate::base::prover::CommitmentScheme::from(CPI::COMMITMENT_SCHEME);
         let query_results = client
             .run_zk_query(QuerySubmitRequest {
                 sql_text: query.to_string(),
-                source_network: SxtNetwork::Mainnet,
+                source_network: self.source_network,
                 timeout: None,
-                commitment_scheme: Some(scheme),
+                commitment_scheme: scheme,
                 block_hash: block_ref.map(|bytes| to_hex(&bytes.to_vec())),
+                params: (!params.is_empty())
+                    .then(|| serialize_query_params(params))
+                    .transpose()?,
             })
-            .await?;
+            .await
+            .context(ZkQueryApiSnafu)?;
         if !query_results.success {
-            return Err(Box::new(std::io::Error::other(format!(
-                "ZK query failed: {}",
-                query_results
+            return QueryFailedSnafu {
+                message: query_results
                     .error
-                    .unwrap_or("Query failed without error".to_string())
-            ))));
+                    .unwrap_or("Query failed without error".to_string()),
+            }
+            .fail();
+        }
+
+        if let Some(block_hash) = block_ref {
+            verify_attested_block(&query_results.commitments, block_hash)?;
         }
+        self.check_attestation_count(&query_results)?;
+        Ok(query_results)
+    }
+
+    /// Query and verify a SQL query at the given SxT block by commitment evaluation proof.
+    ///
+    /// Run a SQL query and verify the result.
+    ///
+    /// If `block_ref` is `None`, the latest block is used.
+    pub async fn query_and_verify_by_cpi<CPI>(
+        &self,
+        query: &str,
+        block_ref: Option<[u8; 32]>,
+        bump: &Bump,
+    ) -> Result<OwnedTable<<CPI as CommitmentEvaluationProof>::Scalar>, SdkError>
+    where
+        CPI: CommitmentEvaluationProofId + 'static,
+        <CPI as CommitmentEvaluationProofId>::DeserializationError: 'static,
+    {
+        self.query_and_verify_by_cpi_with_params::<CPI>(query, &[], block_ref, bump)
+            .await
+    }
+
+    /// Query and verify a parameterized SQL query at the given SxT block by commitment evaluation
+    /// proof.
+    ///
+    /// If `block_ref` is `None`, the latest block is used.
+    pub async fn query_and_verify_by_cpi_with_params<CPI>(
+        &self,
+        query: &str,
+        params: &[LiteralValue],
+        block_ref: Option<[u8; 32]>,
+        bump: &Bump,
+    ) -> Result<OwnedTable<<CPI as CommitmentEvaluationProof>::Scalar>, SdkError>
+    where
+        CPI: CommitmentEvaluationProofId + 'static,
+        <CPI as CommitmentEvaluationProofId>::DeserializationError: 'static,
+    {
+        self.query_and_verify_by_cpi_checked::<CPI>(query, params, block_ref, bump)
+            .await
+            .map(|(table, _)| table)
+    }
+
+    /// Like [`SxTClient::query_and_verify_by_cpi_with_params`], but also returns whether the
+    /// result looks truncated, see [`CheckedResult::looks_truncated`].
+    async fn query_and_verify_by_cpi_checked<CPI>(
+        &self,
+        query: &str,
+        params: &[LiteralValue],
+        block_ref: Option<[u8; 32]>,
+        bump: &Bump,
+    ) -> Result<(OwnedTable<<CPI as CommitmentEvaluationProof>::Scalar>, bool), SdkError>
+    where
+        CPI: CommitmentEvaluationProofId + 'static,
+        <CPI as CommitmentEvaluationProofId>::DeserializationError: 'static,
+    {
+        let statement = parse_single_statement(query)?;
+        // Loading the setup up front reports a bad setup before the query is submitted
+        let (verifier_setup_bytes, _) = self.load_verifier_setup::<CPI>(bump)?;
+        let query_results = self
+            .fetch_query_results::<CPI>(query, params, block_ref)
+            .await?;
+
+        let table_num_rows: IndexMap<TableRef, usize> =
+            extract_query_commitments_from_table_commitments_with_proof::<CPI>(
+                query_results.commitments.commitments.clone(),
+            )
+            .context(VerificationSnafu)?
+            .into_iter()
+            .map(|(table_ref, commitment)| (table_ref, commitment.num_rows()))
+            .collect();
 
-        verify_from_zk_query_and_substrate_responses::<CPI>(query_results, vec![], &verifier_setup)
+        let skip_attestation_verification = self.skip_attestation_verification;
+        let params = params.to_vec();
+        let result = run_blocking(move || {
+            let bump = Bump::new();
+            let verifier_setup =
+                deserialize_loaded_verifier_setup::<CPI>(&verifier_setup_bytes, &bump)?;
+            verify_query_results::<CPI>(
+                skip_attestation_verification,
+                query_results,
+                &params,
+                &verifier_setup,
+            )
+            .map_err(into_send_error)
+        })
+        .await
+        .map_err(|source| SdkError::Verification { source })?;
+
+        let looks_truncated = result_looks_truncated(&statement, result.num_rows(), |table_ref| {
+            table_num_rows.get(table_ref).copied()
+        });
+        Ok((result, looks_truncated))
     }
 
     /// Query and verify a SQL query at the given SxT block
@@ -114,25 +575,1228 @@ impl SxTClient {
     /// Run a SQL query and verify the result.
     ///
     /// If `block_ref` is `None`, the latest block is used.
+    /// If a result cache is enabled and `block_ref` is set, a cached result is returned when available.
     pub async fn query_and_verify(
         &self,
         query: &str,
         block_ref: Option<[u8; 32]>,
         commitment_scheme: CommitmentScheme,
-    ) -> Result<DynOwnedTable, Box<dyn core::error::Error>> {
+    ) -> Result<DynOwnedTable, SdkError> {
+        self.query_and_verify_with_params(query, &[], block_ref, commitment_scheme)
+            .await
+    }
+
+    /// Query and verify a parameterized SQL query at the given SxT block
+    ///
+    /// If `block_ref` is `None`, the latest block is used.
+    /// If a result cache is enabled and `block_ref` is set, a cached result is returned when available.
+    pub async fn query_and_verify_with_params(
+        &self,
+        query: &str,
+        params: &[LiteralValue],
+        block_ref: Option<[u8; 32]>,
+        commitment_scheme: CommitmentScheme,
+    ) -> Result<DynOwnedTable, SdkError> {
+        let cache_key = match (&self.result_cache, block_ref) {
+            (Some(_), Some(block_hash)) => Some(ResultCacheKey {
+                normalized_query: parse_single_statement(query)?.to_string(),
+                params: serialize_query_params(params)?,
+                block_hash,
+                commitment_scheme,
+                skip_attestation_verification: self.skip_attestation_verification,
+                min_attestations: self.min_attestations,
+            }),
+            _ => None,
+        };
+        if let (Some(cache), Some(key)) = (&self.result_cache, &cache_key) {
+            if let Some(table) = cache.get(key) {
+                return Ok(table);
+            }
+        }
+
+        let bump = Bump::new();
+        let table = match commitment_scheme {
+            CommitmentScheme::DynamicDory => self
+                .query_and_verify_by_cpi_with_params::<DynamicDoryEvaluationProof>(
+                    query, params, block_ref, &bump,
+                )
+                .await
+                .map(DynOwnedTable::Dory),
+            #[cfg(feature = "hyperkzg")]
+            CommitmentScheme::HyperKzg => self
+                .query_and_verify_by_cpi_with_params::<HyperKZGCommitmentEvaluationProof>(
+                    query, params, block_ref, &bump,
+                )
+                .await
+                .map(DynOwnedTable::BN),
+        }?;
+
+        if let (Some(cache), Some(key)) = (&self.result_cache, cache_key) {
+            cache.insert(key, table.clone());
+        }
+        Ok(table)
+    }
+
+    /// Query and verify a SQL query at the given SxT block, and check whether the result looks
+    /// truncated.
+    ///
+    /// The ZK Query API does not report when an implicit row limit caps a result, so a plain
+    /// projection over a single table that returns fewer rows than the table holds is flagged.
+    /// The result is verified either way. The result cache is not used.
+    ///
+    /// If `block_ref` is `None`, the latest block is used.
+    pub async fn query_and_verify_checked(
+        &self,
+        query: &str,
+        block_ref: Option<[u8; 32]>,
+        commitment_scheme: CommitmentScheme,
+    ) -> Result<CheckedResult, SdkError> {
+        let bump = Bump::new();
+        let (table, looks_truncated) = match commitment_scheme {
+            CommitmentScheme::DynamicDory => self
+                .query_and_verify_by_cpi_checked::<DynamicDoryEvaluationProof>(
+                    query,
+                    &[],
+                    block_ref,
+                    &bump,
+                )
+                .await
+                .map(|(table, looks_truncated)| (DynOwnedTable::Dory(table), looks_truncated)),
+            #[cfg(feature = "hyperkzg")]
+            CommitmentScheme::HyperKzg => self
+                .query_and_verify_by_cpi_checked::<HyperKZGCommitmentEvaluationProof>(
+                    query,
+                    &[],
+                    block_ref,
+                    &bump,
+                )
+                .await
+                .map(|(table, looks_truncated)| (DynOwnedTable::BN(table), looks_truncated)),
+        }?;
+        Ok(CheckedResult {
+            table,
+            looks_truncated,
+        })
+    }
+
+    /// Query and verify several parameterized SQL queries at the given SxT block.
+    ///
+    /// A single access token is used for the whole batch, and up to [`MAX_BATCH_CONCURRENCY`]
+    /// queries run at the same time. Each response carries the attested commitments of the tables
+    /// its query reads, so attestations are still verified per query. The results are returned in
+    /// the order of `queries`, and one failing query does not fail the others.
+    ///
+    /// If `block_ref` is `None`, each query runs at the latest block when it is submitted, which
+    /// need not be the same block for every query.
+    pub async fn query_and_verify_batch(
+        &self,
+        queries: Vec<(String, Vec<LiteralValue>)>,
+        block_ref: Option<[u8; 32]>,
+        commitment_scheme: CommitmentScheme,
+    ) -> Vec<Result<DynOwnedTable, SdkError>> {
+        let client = match &self.access_token {
+            Some(_) => self.clone(),
+            None => match get_access_token_with_scope(
+                &self.sxt_api_key,
+                self.auth_root_url.as_str(),
+                &self.auth_scope,
+            )
+            .await
+            {
+                Ok(access_token) => self.clone().with_access_token(access_token),
+                Err(error) => {
+                    // The error is not `Clone`, so every query gets its message
+                    let message = error.to_string();
+                    return queries
+                        .iter()
+                        .map(|_| {
+                            Err(SdkError::Authentication {
+                                source: message.clone().into(),
+                            })
+                        })
+                        .collect();
+                }
+            },
+        };
+        let client = &client;
+        stream::iter(queries)
+            .map(|(query, params)| async move {
+                client
+                    .query_and_verify_with_params(&query, &params, block_ref, commitment_scheme)
+                    .await
+            })
+            .buffered(MAX_BATCH_CONCURRENCY)
+            .collect()
+            .await
+    }
+
+    /// Query and verify a SQL query at the given SxT block by commitment evaluation proof, planning
+    /// the query locally.
+    ///
+    /// Rather than trusting the plan returned by the ZK Query API, the plan is produced from the
+    /// attested commitments and the proof is verified against it. If
+    /// [`SxTClient::check_gateway_plan`] is set, the returned plan must also match the local one.
+    ///
+    /// If `block_ref` is `None`, the latest block is used.
+    #[cfg(feature = "trustless-planning")]
+    pub async fn query_and_verify_trustless_by_cpi<CPI>(
+        &self,
+        query: &str,
+        block_ref: Option<[u8; 32]>,
+        bump: &Bump,
+    ) -> Result<OwnedTable<<CPI as CommitmentEvaluationProof>::Scalar>, SdkError>
+    where
+        CPI: CommitmentEvaluationProofId + 'static,
+        <CPI as CommitmentEvaluationProofId>::DeserializationError: 'static,
+    {
+        let statement = parse_single_statement(query)?;
+        // Loading the setup up front reports a bad setup before the query is submitted
+        let (verifier_setup_bytes, _) = self.load_verifier_setup::<CPI>(bump)?;
+        let query_results = self
+            .fetch_query_results::<CPI>(query, &[], block_ref)
+            .await?;
+        let gateway_plan = query_results.plan.clone();
+        let check_gateway_plan = self.check_gateway_plan;
+
+        run_blocking(move || {
+            let bump = Bump::new();
+            let verifier_setup =
+                deserialize_loaded_verifier_setup::<CPI>(&verifier_setup_bytes, &bump)?;
+            verify_from_zk_query_and_substrate_responses_with_plan::<CPI>(
+                query_results,
+                vec![],
+                &[],
+                &verifier_setup,
+                |query_commitments| {
+                    let plan = EVMProofPlan::new(produce_plan_trustlessly::<CPI>(
+                        &statement,
+                        query_commitments,
+                    )?);
+                    if check_gateway_plan
+                        && try_standard_binary_serialization(&plan)? != gateway_plan
+                    {
+                        return Err(Box::new(VerifyProverResponseError::PlanMismatch));
+                    }
+                    Ok(plan)
+                },
+            )
+            .map_err(into_send_error)
+        })
+        .await
+        .map_err(|source| SdkError::Verification { source })
+    }
+
+    /// Query and verify a SQL query at the given SxT block with HyperKZG, returning the same JSON
+    /// envelope as the JavaScript bindings.
+    ///
+    /// Failures are reported as a `Failure` envelope rather than an `Err`, so the result can be
+    /// returned from an HTTP handler as-is. Only a result that fails verification is reported as a
+    /// `VerificationError`; a query that could not be run at all is a `QueryError`.
+    #[cfg(feature = "hyperkzg")]
+    pub async fn query_and_verify_json(
+        &self,
+        query: &str,
+        block_ref: Option<[u8; 32]>,
+        options: JsonResultOptions,
+    ) -> String {
+        let bump = Bump::new();
+        let result = self
+            .query_and_verify_by_cpi::<HyperKZGCommitmentEvaluationProof>(query, block_ref, &bump)
+            .await
+            .map_err(|err| match err {
+                SdkError::Attestation { .. } | SdkError::Verification { .. } => {
+                    Failure::VerificationError(format!("Error verifying result: {err}"))
+                }
+                SdkError::ResultConversion { .. } => {
+                    Failure::TypeConversion(format!("Error converting result: {err}"))
+                }
+                SdkError::InvalidQuery { .. }
+                | SdkError::InvalidParams { .. }
+                | SdkError::VerifierSetup { .. }
+                | SdkError::Authentication { .. }
+                | SdkError::ZkQueryApi { .. }
+                | SdkError::QueryFailed { .. } => {
+                    Failure::QueryError(format!("Error running query: {err}"))
+                }
+            })
+            .and_then(|table| try_convert_table_to_javascript_friendly_table(table, &options));
+        serialize_javascript_friendly_type(result.into())
+    }
+
+    /// Query and verify a SQL query at the given SxT block, planning the query locally.
+    ///
+    /// See [`SxTClient::query_and_verify_trustless_by_cpi`].
+    #[cfg(feature = "trustless-planning")]
+    pub async fn query_and_verify_trustless(
+        &self,
+        query: &str,
+        block_ref: Option<[u8; 32]>,
+        commitment_scheme: CommitmentScheme,
+    ) -> Result<DynOwnedTable, SdkError> {
         let bump = Bump::new();
         match commitment_scheme {
             CommitmentScheme::DynamicDory => self
-                .query_and_verify_by_cpi::<DynamicDoryEvaluationProof>(query, block_ref, &bump)
+                .query_and_verify_trustless_by_cpi::<DynamicDoryEvaluationProof>(
+                    query, block_ref, &bump,
+                )
                 .await
                 .map(DynOwnedTable::Dory),
             #[cfg(feature = "hyperkzg")]
             CommitmentScheme::HyperKzg => self
-                .query_and_verify_by_cpi::<HyperKZGCommitmentEvaluationProof>(
+                .query_and_verify_trustless_by_cpi::<HyperKZGCommitmentEvaluationProof>(
                     query, block_ref, &bump,
                 )
                 .await
                 .map(DynOwnedTable::BN),
         }
     }
+
+    /// Verify a ZK Query API response obtained elsewhere by commitment evaluation proof.
+    ///
+    /// The attestations and proof are checked without contacting the ZK Query API or the auth
+    /// service. If `block_ref` is set, the attestations must be for that block.
+    pub fn verify_only_by_cpi<CPI>(
+        &self,
+        query_results: QueryResultsResponse,
+        params: &[LiteralValue],
+        block_ref: Option<[u8; 32]>,
+        bump: &Bump,
+    ) -> Result<OwnedTable<<CPI as CommitmentEvaluationProof>::Scalar>, SdkError>
+    where
+        CPI: CommitmentEvaluationProofId,
+        <CPI as CommitmentEvaluationProofId>::DeserializationError: 'static,
+    {
+        let (_, verifier_setup) = self.load_verifier_setup::<CPI>(bump)?;
+        if let Some(block_hash) = block_ref {
+            verify_attested_block(&query_results.commitments, block_hash)?;
+        }
+        self.check_attestation_count(&query_results)?;
+        verify_query_results::<CPI>(
+            self.skip_attestation_verification,
+            query_results,
+            params,
+            &verifier_setup,
+        )
+        .context(VerificationSnafu)
+    }
+
+    /// Verify a ZK Query API response obtained elsewhere.
+    ///
+    /// See [`SxTClient::verify_only_by_cpi`].
+    pub fn verify_only(
+        &self,
+        query_results: QueryResultsResponse,
+        params: &[LiteralValue],
+        block_ref: Option<[u8; 32]>,
+        commitment_scheme: CommitmentScheme,
+    ) -> Result<DynOwnedTable, SdkError> {
+        let bump = Bump::new();
+        match commitment_scheme {
+            CommitmentScheme::DynamicDory => self
+                .verify_only_by_cpi::<DynamicDoryEvaluationProof>(
+                    query_results,
+                    params,
+                    block_ref,
+                    &bump,
+                )
+                .map(DynOwnedTable::Dory),
+            #[cfg(feature = "hyperkzg")]
+            CommitmentScheme::HyperKzg => self
+                .verify_only_by_cpi::<HyperKZGCommitmentEvaluationProof>(
+                    query_results,
+                    params,
+                    block_ref,
+                    &bump,
+                )
+                .map(DynOwnedTable::BN),
+        }
+    }
+
+    /// Describe a table by commitment evaluation proof, reading its columns and their types from
+    /// its attested commitment.
+    ///
+    /// The ZK Query API only returns commitments alongside a query, so this runs a `COUNT(*)` over
+    /// the table to obtain them. Its attestations are verified unless
+    /// `skip_attestation_verification` is set.
+    ///
+    /// If `block_ref` is `None`, the latest block is used.
+    pub async fn describe_table_by_cpi<CPI: CommitmentEvaluationProofId>(
+        &self,
+        table_ref: &TableRef,
+        block_ref: Option<[u8; 32]>,
+    ) -> Result<TableSchema, SdkError> {
+        let table_ref = canonicalize_table_ref(table_ref);
+        let query_results = self
+            .fetch_query_results::<CPI>(
+                &format!("SELECT COUNT(*) FROM {table_ref}"),
+                &[],
+                block_ref,
+            )
+            .await?;
+        let block_number = query_results.commitments.block_number;
+        let table_commitments = if self.skip_attestation_verification {
+            query_results.commitments.commitments
+        } else {
+            verify_attestations(&query_results.commitments, vec![], CPI::COMMITMENT_SCHEME)?
+        };
+        let query_commitments =
+            extract_query_commitments_from_table_commitments_with_proof::<CPI>(table_commitments)
+                .context(VerificationSnafu)?;
+        let Some(table_commitment) = query_commitments.get(&table_ref) else {
+            return Err(SdkError::Verification {
+                source: Box::new(VerifyProverResponseError::CommitmentNotFound {
+                    table: table_ref,
+                    scheme: CPI::COMMITMENT_SCHEME,
+                    block_number,
+                }),
+            });
+        };
+        Ok(TableSchema::from_table_commitment(
+            table_ref,
+            table_commitment,
+        ))
+    }
+
+    /// Describe a table, reading its columns and their types from its attested commitment.
+    ///
+    /// See [`SxTClient::describe_table_by_cpi`].
+    pub async fn describe_table(
+        &self,
+        table_ref: &TableRef,
+        commitment_scheme: CommitmentScheme,
+        block_ref: Option<[u8; 32]>,
+    ) -> Result<TableSchema, SdkError> {
+        match commitment_scheme {
+            CommitmentScheme::DynamicDory => {
+                self.describe_table_by_cpi::<DynamicDoryEvaluationProof>(table_ref, block_ref)
+                    .await
+            }
+            #[cfg(feature = "hyperkzg")]
+            CommitmentScheme::HyperKzg => {
+                self.describe_table_by_cpi::<HyperKZGCommitmentEvaluationProof>(
+                    table_ref, block_ref,
+                )
+                .await
+            }
+        }
+    }
+
+    /// Query and verify a SQL query at the given SxT block, streaming the result in chunks.
+    ///
+    /// The full result is verified before anything is emitted. It is then sent as record batches
+    /// of at most `chunk_size` rows through a bounded channel, so consumers can process large
+    /// results incrementally.
+    ///
+    /// Must be called from within a tokio runtime.
+    pub async fn query_and_verify_stream(
+        &self,
+        query: &str,
+        block_ref: Option<[u8; 32]>,
+        commitment_scheme: CommitmentScheme,
+        chunk_size: usize,
+    ) -> Result<mpsc::Receiver<RecordBatch>, SdkError> {
+        let table = self
+            .query_and_verify(query, block_ref, commitment_scheme)
+            .await?;
+        let batch = RecordBatch::try_from(table)?;
+        Ok(stream_record_batch(batch, chunk_size))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use datafusion::arrow::compute::concat_batches;
+    use proof_of_sql::base::database::OwnedColumn;
+    use sqlparser::ast::Ident;
+
+    fn parse(sql: &str) -> Statement {
+        parse_single_statement(sql).unwrap()
+    }
+
+    fn blocks_num_rows(table_ref: &TableRef) -> Option<usize> {
+        (*table_ref == TableRef::try_from("ETHEREUM.BLOCKS").unwrap()).then_some(100)
+    }
+
+    #[test]
+    fn we_can_detect_a_capped_plain_projection() {
+        assert!(result_looks_truncated(
+            &parse("SELECT * FROM ethereum.blocks"),
+            10,
+            blocks_num_rows
+        ));
+        assert!(result_looks_truncated(
+            &parse("SELECT block_number AS n FROM ETHEREUM.BLOCKS"),
+            99,
+            blocks_num_rows
+        ));
+    }
+
+    #[test]
+    fn we_do_not_flag_complete_or_filtered_results() {
+        assert!(!result_looks_truncated(
+            &parse("SELECT * FROM ethereum.blocks"),
+            100,
+            blocks_num_rows
+        ));
+        assert!(!result_looks_truncated(
+            &parse("SELECT * FROM ethereum.blocks WHERE block_number > 5"),
+            10,
+            blocks_num_rows
+        ));
+        assert!(!result_looks_truncated(
+            &parse("SELECT * FROM ethereum.blocks LIMIT 10"),
+            10,
+            blocks_num_rows
+        ));
+        assert!(!result_looks_truncated(
+            &parse("SELECT COUNT(*) FROM ethereum.blocks"),
+            1,
+            blocks_num_rows
+        ));
+        assert!(!result_looks_truncated(
+            &parse("SELECT * FROM ethereum.transactions"),
+            10,
+            blocks_num_rows
+        ));
+    }
+
+    async fn drain(mut receiver: mpsc::Receiver<RecordBatch>) -> Vec<RecordBatch> {
+        let mut batches = Vec::new();
+        while let Some(batch) = receiver.recv().await {
+            batches.push(batch);
+        }
+        batches
+    }
+
+    fn record_batch(block_numbers: Vec<i64>) -> RecordBatch {
+        let table = DynOwnedTable::Dory(
+            OwnedTable::try_new(
+                [(
+                    Ident::new("BLOCK_NUMBER"),
+                    OwnedColumn::BigInt(block_numbers),
+                )]
+                .into_iter()
+                .collect(),
+            )
+            .unwrap(),
+        );
+        RecordBatch::try_from(table).unwrap()
+    }
+
+    #[tokio::test]
+    async fn we_can_reassemble_a_streamed_result() {
+        let batch = record_batch((0..10).collect());
+        let batches = drain(stream_record_batch(batch.clone(), 3)).await;
+
+        assert_eq!(
+            batches
+                .iter()
+                .map(RecordBatch::num_rows)
+                .collect::<Vec<_>>(),
+            vec![3, 3, 3, 1]
+        );
+        assert_eq!(concat_batches(&batch.schema(), &batches).unwrap(), batch);
+    }
+
+    #[tokio::test]
+    async fn we_stream_an_empty_result_as_a_single_batch() {
+        let batch = record_batch(vec![]);
+        let batches = drain(stream_record_batch(batch.clone(), 3)).await;
+        assert_eq!(batches, vec![batch]);
+    }
+
+    #[cfg(all(feature = "trustless-planning", feature = "hyperkzg"))]
+    #[test]
+    fn we_can_plan_locally_from_attested_commitments() {
+        use crate::base::zk_query_models::query_results_response_from_json;
+        use proof_of_sql::sql::proof::ProofPlan;
+
+        let query_results = query_results_response_from_json(include_str!(
+            "../../../../test_assets/valid_gateway_response.json"
+        ))
+        .unwrap();
+        let query_commitments = extract_query_commitments_from_table_commitments_with_proof::<
+            HyperKZGCommitmentEvaluationProof,
+        >(query_results.commitments.commitments.clone())
+        .unwrap();
+
+        let plan = produce_plan_trustlessly::<HyperKZGCommitmentEvaluationProof>(
+            &parse("SELECT block_number FROM ethereum.blocks GROUP BY block_number"),
+            &query_commitments,
+        )
+        .unwrap();
+        assert_eq!(
+            plan.get_table_references().into_iter().collect::<Vec<_>>(),
+            vec![TableRef::try_from("ETHEREUM.BLOCKS").unwrap()]
+        );
+
+        assert!(
+            produce_plan_trustlessly::<HyperKZGCommitmentEvaluationProof>(
+                &parse("SELECT hash FROM ethereum.transactions"),
+                &query_commitments,
+            )
+            .is_err()
+        );
+
+        // The proof in the response verifies against the locally produced plan
+        let bump = Bump::new();
+        let verifier_setup = HyperKZGCommitmentEvaluationProof::deserialize_verifier_setup(
+            HyperKZGCommitmentEvaluationProof::DEFAULT_VERIFIER_SETUP_BYTES,
+            &bump,
+        )
+        .unwrap();
+        let table = verify_from_zk_query_and_substrate_responses_with_plan::<
+            HyperKZGCommitmentEvaluationProof,
+        >(query_results, vec![], &[], &verifier_setup, |_| {
+            Ok(EVMProofPlan::new(plan))
+        })
+        .unwrap();
+        assert_eq!(
+            table.inner_table()[&Ident::new("BLOCK_NUMBER")],
+            OwnedColumn::BigInt(vec![22432845])
+        );
+    }
+
+    #[cfg(all(feature = "trustless-planning", feature = "hyperkzg"))]
+    #[tokio::test]
+    async fn we_reject_a_gateway_plan_for_a_different_query() {
+        use crate::native::mock_server::{fixture_routes, mock_server};
+
+        let (base_url, _) = mock_server(fixture_routes());
+        let client = SxTClient::new(
+            SxtNetwork::Mainnet,
+            base_url.clone(),
+            base_url.join("proxy").unwrap(),
+            "key".to_string(),
+            None,
+        )
+        .with_gateway_plan_check();
+
+        // The mock API returns a grouped plan, which does not match this plain projection
+        let error = client
+            .query_and_verify_trustless(
+                "SELECT block_number FROM ethereum.blocks",
+                None,
+                CommitmentScheme::HyperKzg,
+            )
+            .await
+            .unwrap_err();
+
+        let SdkError::Verification { source } = error else {
+            panic!("expected a verification error");
+        };
+        assert!(matches!(
+            source.downcast_ref::<VerifyProverResponseError>(),
+            Some(VerifyProverResponseError::PlanMismatch)
+        ));
+    }
+
+    #[cfg(feature = "hyperkzg")]
+    #[tokio::test(flavor = "multi_thread")]
+    async fn we_can_verify_on_a_multi_threaded_runtime() {
+        use crate::native::mock_server::{fixture_routes, mock_server};
+
+        let (base_url, _) = mock_server(fixture_routes());
+        let client = SxTClient::new(
+            SxtNetwork::Mainnet,
+            base_url.clone(),
+            base_url.join("proxy").unwrap(),
+            "key".to_string(),
+            None,
+        );
+
+        let table = client
+            .query_and_verify(
+                "SELECT block_number FROM ethereum.blocks GROUP BY block_number",
+                None,
+                CommitmentScheme::HyperKzg,
+            )
+            .await
+            .unwrap();
+
+        let DynOwnedTable::BN(table) = table else {
+            panic!("expected a HyperKZG table");
+        };
+        assert_eq!(
+            table.inner_table()[&Ident::new("BLOCK_NUMBER")],
+            OwnedColumn::BigInt(vec![22432845])
+        );
+    }
+
+    #[cfg(feature = "hyperkzg")]
+    #[tokio::test]
+    async fn we_submit_queries_with_the_requested_commitment_scheme() {
+        use crate::native::mock_server::{fixture_routes, mock_server};
+
+        let (base_url, requests) = mock_server(fixture_routes());
+        let client = SxTClient::new(
+            SxtNetwork::Mainnet,
+            base_url.clone(),
+            base_url.join("proxy").unwrap(),
+            "key".to_string(),
+            None,
+        );
+
+        client
+            .query_and_verify(
+                "SELECT block_number FROM ethereum.blocks GROUP BY block_number",
+                None,
+                CommitmentScheme::HyperKzg,
+            )
+            .await
+            .unwrap();
+
+        let requests = requests.lock().unwrap();
+        let (_, submit_body) = requests
+            .iter()
+            .find(|(request_line, _)| request_line.starts_with("POST /v1/zkquery "))
+            .unwrap();
+        let submit_body: serde_json::Value = serde_json::from_str(submit_body).unwrap();
+        assert_eq!(submit_body["commitmentScheme"], "HYPER_KZG");
+    }
+
+    #[cfg(feature = "hyperkzg")]
+    #[tokio::test]
+    async fn we_request_tokens_for_the_configured_auth_scope() {
+        use crate::native::mock_server::{fixture_routes, mock_server};
+
+        let (base_url, requests) = mock_server(fixture_routes());
+        let client = SxTClient::new(
+            SxtNetwork::Mainnet,
+            base_url.clone(),
+            base_url.join("proxy").unwrap(),
+            "key".to_string(),
+            None,
+        )
+        .with_auth_scope(AuthScope {
+            scope: Some("zkquery:read".to_string()),
+            audience: Some("https://api.makeinfinite.dev".to_string()),
+        });
+
+        client
+            .query_and_verify(
+                "SELECT block_number FROM ethereum.blocks GROUP BY block_number",
+                None,
+                CommitmentScheme::HyperKzg,
+            )
+            .await
+            .unwrap();
+
+        let requests = requests.lock().unwrap();
+        let (_, auth_body) = requests
+            .iter()
+            .find(|(request_line, _)| request_line.starts_with("POST /proxy/auth/apikey "))
+            .unwrap();
+        let auth_body: serde_json::Value = serde_json::from_str(auth_body).unwrap();
+        assert_eq!(auth_body["scope"], "zkquery:read");
+        assert_eq!(auth_body["audience"], "https://api.makeinfinite.dev");
+    }
+
+    #[cfg(feature = "hyperkzg")]
+    #[tokio::test]
+    async fn we_can_run_a_batch_of_queries_with_one_access_token() {
+        use crate::native::mock_server::{fixture_routes, mock_server, request_lines};
+
+        let (base_url, requests) = mock_server(fixture_routes());
+        let client = SxTClient::new(
+            SxtNetwork::Mainnet,
+            base_url.clone(),
+            base_url.join("proxy").unwrap(),
+            "key".to_string(),
+            None,
+        );
+
+        let results = client
+            .query_and_verify_batch(
+                vec![
+                    (
+                        "SELECT block_number FROM ethereum.blocks GROUP BY block_number"
+                            .to_string(),
+                        vec![],
+                    ),
+                    (
+                        "select block_number from ethereum.blocks group by block_number"
+                            .to_string(),
+                        vec![],
+                    ),
+                ],
+                None,
+                CommitmentScheme::HyperKzg,
+            )
+            .await;
+
+        assert_eq!(results.len(), 2);
+        for result in results {
+            let DynOwnedTable::BN(table) = result.unwrap() else {
+                panic!("expected a HyperKZG table");
+            };
+            assert_eq!(
+                table.inner_table()[&Ident::new("BLOCK_NUMBER")],
+                OwnedColumn::BigInt(vec![22432845])
+            );
+        }
+        let request_lines = request_lines(&requests);
+        assert_eq!(
+            request_lines
+                .iter()
+                .filter(|line| line.starts_with("POST /proxy/auth/apikey "))
+                .count(),
+            1
+        );
+        assert_eq!(
+            request_lines
+                .iter()
+                .filter(|line| line.starts_with("POST /v1/zkquery "))
+                .count(),
+            2
+        );
+    }
+
+    #[cfg(feature = "hyperkzg")]
+    #[tokio::test]
+    async fn we_report_whether_a_verified_result_looks_truncated() {
+        use crate::native::mock_server::{fixture_routes, mock_server};
+
+        let (base_url, _) = mock_server(fixture_routes());
+        let client = SxTClient::new(
+            SxtNetwork::Mainnet,
+            base_url.clone(),
+            base_url.join("proxy").unwrap(),
+            "key".to_string(),
+            None,
+        );
+
+        // The fixture result has one row, while ETHEREUM.BLOCKS has many more
+        let grouped = client
+            .query_and_verify_checked(
+                "SELECT block_number FROM ethereum.blocks GROUP BY block_number",
+                None,
+                CommitmentScheme::HyperKzg,
+            )
+            .await
+            .unwrap();
+        assert!(!grouped.looks_truncated);
+        let projected = client
+            .query_and_verify_checked(
+                "SELECT block_number FROM ethereum.blocks",
+                None,
+                CommitmentScheme::HyperKzg,
+            )
+            .await
+            .unwrap();
+        assert!(projected.looks_truncated);
+        assert_eq!(projected.table, grouped.table);
+    }
+
+    #[cfg(feature = "hyperkzg")]
+    #[tokio::test]
+    async fn we_reject_a_response_over_the_clients_size_cap() {
+        use crate::native::{
+            mock_server::{fixture_routes, mock_server},
+            ZkQueryClientError,
+        };
+
+        let (base_url, _) = mock_server(fixture_routes());
+        let client = SxTClient::new(
+            SxtNetwork::Mainnet,
+            base_url.clone(),
+            base_url.join("proxy").unwrap(),
+            "key".to_string(),
+            None,
+        )
+        .with_max_response_bytes(1024);
+
+        let error = client
+            .query_and_verify(
+                "SELECT block_number FROM ethereum.blocks GROUP BY block_number",
+                None,
+                CommitmentScheme::HyperKzg,
+            )
+            .await
+            .unwrap_err();
+        let SdkError::ZkQueryApi { source } = error else {
+            panic!("expected a ZK Query API error, got {error:?}");
+        };
+        assert!(matches!(
+            source.downcast_ref::<ZkQueryClientError>(),
+            Some(ZkQueryClientError::ResponseTooLarge {
+                max_response_bytes: 1024
+            })
+        ));
+    }
+
+    #[cfg(feature = "hyperkzg")]
+    #[tokio::test]
+    async fn we_do_not_request_an_access_token_when_one_is_set() {
+        use crate::native::mock_server::{fixture_routes, mock_server, request_lines};
+
+        let (base_url, requests) = mock_server(fixture_routes());
+        let client = SxTClient::new(
+            SxtNetwork::Mainnet,
+            base_url.clone(),
+            base_url.join("proxy").unwrap(),
+            "key".to_string(),
+            None,
+        )
+        .with_access_token("token".to_string());
+
+        client
+            .query_and_verify(
+                "SELECT block_number FROM ethereum.blocks GROUP BY block_number",
+                None,
+                CommitmentScheme::HyperKzg,
+            )
+            .await
+            .unwrap();
+
+        assert!(!request_lines(&requests)
+            .iter()
+            .any(|line| line.starts_with("POST /proxy/auth/apikey ")));
+    }
+
+    #[cfg(feature = "hyperkzg")]
+    #[tokio::test]
+    async fn we_can_describe_a_table_from_its_commitment() {
+        use crate::native::mock_server::{fixture_routes, mock_server};
+        use proof_of_sql::base::{database::ColumnType, math::decimal::Precision};
+
+        let (base_url, _) = mock_server(fixture_routes());
+        let client = SxTClient::new(
+            SxtNetwork::Mainnet,
+            base_url.clone(),
+            base_url.join("proxy").unwrap(),
+            "key".to_string(),
+            None,
+        );
+
+        let schema = client
+            .describe_table(
+                &TableRef::try_from("ethereum.blocks").unwrap(),
+                CommitmentScheme::HyperKzg,
+                None,
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(
+            schema.table_ref,
+            TableRef::try_from("ETHEREUM.BLOCKS").unwrap()
+        );
+        assert!(matches!(
+            schema.columns[&Ident::new("TIME_STAMP")],
+            ColumnType::TimestampTZ(..)
+        ));
+        let decimal = ColumnType::Decimal75(Precision::new(75).unwrap(), 0);
+        let expected_columns = [
+            ("BLOCK_NUMBER", ColumnType::BigInt),
+            ("BLOCK_HASH", ColumnType::VarBinary),
+            ("GAS_LIMIT", decimal),
+            ("GAS_USED", decimal),
+            ("MINER", ColumnType::VarBinary),
+            ("PARENT_HASH", ColumnType::VarBinary),
+            ("REWARD", decimal),
+            ("SIZE", ColumnType::BigInt),
+            ("TRANSACTION_COUNT", ColumnType::Int),
+            ("NONCE", ColumnType::VarBinary),
+            ("RECEIPTS_ROOT", ColumnType::VarBinary),
+            ("SHA3_UNCLES", ColumnType::VarBinary),
+            ("STATE_ROOT", ColumnType::VarBinary),
+            ("TRANSACTIONS_ROOT", ColumnType::VarBinary),
+            ("UNCLES_COUNT", ColumnType::BigInt),
+        ];
+        assert_eq!(schema.columns.len(), 16);
+        for (name, column_type) in expected_columns {
+            assert_eq!(schema.columns[&Ident::new(name)], column_type, "{name}");
+        }
+    }
+
+    #[cfg(feature = "hyperkzg")]
+    #[tokio::test]
+    async fn we_cannot_describe_a_table_without_a_commitment() {
+        use crate::native::mock_server::{fixture_routes, mock_server};
+
+        let (base_url, _) = mock_server(fixture_routes());
+        let client = SxTClient::new(
+            SxtNetwork::Mainnet,
+            base_url.clone(),
+            base_url.join("proxy").unwrap(),
+            "key".to_string(),
+            None,
+        );
+
+        // The mock API only returns the commitment for `ETHEREUM.BLOCKS`
+        let error = client
+            .describe_table(
+                &TableRef::try_from("ethereum.transactions").unwrap(),
+                CommitmentScheme::HyperKzg,
+                None,
+            )
+            .await
+            .unwrap_err();
+
+        let SdkError::Verification { source } = error else {
+            panic!("expected a verification error");
+        };
+        assert!(matches!(
+            source.downcast_ref::<VerifyProverResponseError>(),
+            Some(VerifyProverResponseError::CommitmentNotFound {
+                block_number: 9083634,
+                ..
+            })
+        ));
+    }
+
+    #[cfg(feature = "hyperkzg")]
+    #[tokio::test]
+    async fn we_can_get_a_verified_result_as_a_json_envelope() {
+        use crate::native::mock_server::{fixture_routes, mock_server};
+
+        let (base_url, _) = mock_server(fixture_routes());
+        let client = SxTClient::new(
+            SxtNetwork::Mainnet,
+            base_url.clone(),
+            base_url.join("proxy").unwrap(),
+            "key".to_string(),
+            None,
+        );
+
+        let json = client
+            .query_and_verify_json(
+                "SELECT block_number FROM ethereum.blocks GROUP BY block_number",
+                None,
+                JsonResultOptions::default(),
+            )
+            .await;
+
+        let envelope: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(envelope["verificationStatus"], "Success");
+        assert_eq!(
+            envelope["result"]["BLOCK_NUMBER"],
+            serde_json::json!({ "type": "BigInt", "column": ["22432845"] })
+        );
+    }
+
+    #[cfg(feature = "hyperkzg")]
+    #[tokio::test]
+    async fn we_get_a_failure_envelope_instead_of_an_error() {
+        let json = offline_client()
+            .query_and_verify_json(
+                "SELECT * FROM ethereum.blocks",
+                None,
+                JsonResultOptions::default(),
+            )
+            .await;
+
+        let envelope: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(envelope["verificationStatus"], "Failure");
+        assert_eq!(envelope["error"], "QueryError");
+        assert!(envelope["message"]
+            .as_str()
+            .unwrap()
+            .contains("unable to authenticate"));
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+    async fn we_can_abort_an_in_flight_verification() {
+        let (started_sender, started_receiver) = tokio::sync::oneshot::channel();
+        let (release_sender, release_receiver) = std::sync::mpsc::channel::<()>();
+        let verification = tokio::spawn(run_blocking(move || {
+            started_sender.send(()).unwrap();
+            // Stands in for a long verification
+            release_receiver.recv().unwrap();
+        }));
+        started_receiver.await.unwrap();
+
+        // The only worker thread is not pinned by the blocking work
+        assert_eq!(tokio::spawn(async { 1 + 1 }).await.unwrap(), 2);
+
+        verification.abort();
+        assert!(verification.await.unwrap_err().is_cancelled());
+        release_sender.send(()).unwrap();
+    }
+
+    #[cfg(feature = "hyperkzg")]
+    #[tokio::test]
+    async fn we_return_an_sdk_error_when_a_streamed_query_fails() {
+        let error = offline_client()
+            .query_and_verify_stream(
+                "SELECT * FROM ethereum.blocks",
+                None,
+                CommitmentScheme::DynamicDory,
+                10,
+            )
+            .await
+            .unwrap_err();
+
+        assert!(matches!(error, SdkError::Authentication { .. }));
+        assert_eq!(
+            crate::exit_code::CliExitCode::for_error(&error),
+            crate::exit_code::CliExitCode::Network
+        );
+    }
+
+    fn offline_client() -> SxTClient {
+        SxTClient::new(
+            SxtNetwork::Mainnet,
+            Url::parse("http://127.0.0.1:1").unwrap(),
+            Url::parse("http://127.0.0.1:1").unwrap(),
+            "unused".to_string(),
+            None,
+        )
+    }
+
+    #[cfg(feature = "hyperkzg")]
+    fn fixture_query_results() -> QueryResultsResponse {
+        crate::base::zk_query_models::query_results_response_from_json(include_str!(
+            "../../../../test_assets/valid_gateway_response.json"
+        ))
+        .unwrap()
+    }
+
+    #[cfg(feature = "hyperkzg")]
+    #[test]
+    fn we_can_verify_a_response_without_fetching() {
+        let query_results = fixture_query_results();
+        let block_hash = query_results.commitments.block_hash;
+
+        let table = offline_client()
+            .verify_only(
+                query_results,
+                &[],
+                Some(block_hash),
+                CommitmentScheme::HyperKzg,
+            )
+            .unwrap();
+
+        let DynOwnedTable::BN(table) = table else {
+            panic!("expected a HyperKZG table");
+        };
+        assert_eq!(
+            table.inner_table()[&Ident::new("BLOCK_NUMBER")],
+            OwnedColumn::BigInt(vec![22432845])
+        );
+    }
+
+    #[cfg(feature = "hyperkzg")]
+    #[test]
+    fn we_cannot_verify_a_response_for_a_different_block_without_fetching() {
+        assert!(matches!(
+            offline_client().verify_only(
+                fixture_query_results(),
+                &[],
+                Some([0; 32]),
+                CommitmentScheme::HyperKzg,
+            ),
+            Err(SdkError::Attestation {
+                source: crate::base::attestation::AttestationError::BlockHashMismatch { .. }
+            })
+        ));
+    }
+
+    #[cfg(feature = "hyperkzg")]
+    #[test]
+    fn we_can_skip_attestation_verification() {
+        // Attestations claiming to be from other signers no longer recover to their addresses
+        let tampered_query_results = || {
+            let mut query_results = fixture_query_results();
+            for (i, address20) in query_results.commitments.address20s.iter_mut().enumerate() {
+                *address20 = vec![0xf0 + i as u8; 20];
+            }
+            query_results
+        };
+        assert!(matches!(
+            offline_client().verify_only(
+                tampered_query_results(),
+                &[],
+                None,
+                CommitmentScheme::HyperKzg
+            ),
+            Err(SdkError::Verification { .. })
+        ));
+
+        let table = offline_client()
+            .without_attestation_verification()
+            .verify_only(
+                tampered_query_results(),
+                &[],
+                None,
+                CommitmentScheme::HyperKzg,
+            )
+            .unwrap();
+        let DynOwnedTable::BN(table) = table else {
+            panic!("expected a HyperKZG table");
+        };
+        assert_eq!(
+            table.inner_table()[&Ident::new("BLOCK_NUMBER")],
+            OwnedColumn::BigInt(vec![22432845])
+        );
+    }
+
+    #[cfg(feature = "hyperkzg")]
+    #[test]
+    fn we_cannot_verify_a_response_with_too_few_attestations() {
+        // Keep only the first of the fixture's three attestations
+        let mut query_results = fixture_query_results();
+        let commitments = &mut query_results.commitments;
+        commitments.r.truncate(1);
+        commitments.s.truncate(1);
+        commitments.v.truncate(1);
+        commitments.state_root.truncate(1);
+        commitments.address20s.truncate(1);
+
+        assert!(matches!(
+            offline_client().verify_only(query_results, &[], None, CommitmentScheme::HyperKzg),
+            Err(SdkError::Attestation {
+                source: crate::base::attestation::AttestationError::InsufficientAttestations {
+                    found: 1,
+                    required: DEFAULT_MIN_ATTESTATIONS
+                }
+            })
+        ));
+
+        assert!(matches!(
+            offline_client().with_min_attestations(4).verify_only(
+                fixture_query_results(),
+                &[],
+                None,
+                CommitmentScheme::HyperKzg
+            ),
+            Err(SdkError::Attestation {
+                source: crate::base::attestation::AttestationError::InsufficientAttestations {
+                    found: 3,
+                    required: 4
+                }
+            })
+        ));
+    }
+
+    #[cfg(feature = "hyperkzg")]
+    #[tokio::test]
+    async fn we_surface_an_invalid_query_as_an_invalid_query_error() {
+        assert!(matches!(
+            offline_client()
+                .query_and_verify("", None, CommitmentScheme::HyperKzg)
+                .await,
+            Err(SdkError::InvalidQuery { .. })
+        ));
+    }
+
+    #[cfg(feature = "hyperkzg")]
+    #[tokio::test]
+    async fn we_surface_an_unreachable_auth_service_as_an_authentication_error() {
+        let error = offline_client()
+            .query_and_verify(
+                "SELECT * FROM ethereum.blocks",
+                None,
+                CommitmentScheme::HyperKzg,
+            )
+            .await
+            .unwrap_err();
+        assert!(matches!(error, SdkError::Authentication { .. }));
+
+        // The cause chain survives the conversion to a boxed error
+        let error: Box<dyn core::error::Error> = error.into();
+        assert!(error.source().is_some());
+    }
 }
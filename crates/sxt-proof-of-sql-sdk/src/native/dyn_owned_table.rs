@@ -1,19 +1,175 @@
+use core::cmp::Ordering;
 use datafusion::arrow::{error::ArrowError, record_batch::RecordBatch};
+use indexmap::IndexMap;
 #[cfg(feature = "hyperkzg")]
 use proof_of_sql::proof_primitive::hyperkzg::BNScalar;
-use proof_of_sql::{base::database::OwnedTable, proof_primitive::dory::DoryScalar};
+use proof_of_sql::{
+    base::{
+        database::{ColumnType, OwnedColumn, OwnedTable},
+        scalar::Scalar,
+    },
+    proof_primitive::dory::DoryScalar,
+};
 use serde::{Deserialize, Serialize};
+use snafu::{OptionExt, Snafu};
 
 /// Enum of [`OwnedTable`]s with different scalar types.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+///
+/// Serialized with the commitment scheme as an explicit tag, e.g.
+/// `{"scheme": "DynamicDory", "table": ...}`, so a persisted table reloads into the right variant.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "scheme", content = "table")]
 pub enum DynOwnedTable {
     /// Owned table with a [`DoryScalar`]. Used for Dynamic Dory.
+    #[serde(rename = "DynamicDory")]
     Dory(OwnedTable<DoryScalar>),
     /// Owned table with a [`BNScalar`]. Used for HyperKZG.
     #[cfg(feature = "hyperkzg")]
+    #[serde(rename = "HyperKzg")]
     BN(OwnedTable<BNScalar>),
 }
 
+/// Errors that can occur when sorting a [`DynOwnedTable`].
+#[derive(Snafu, Debug, PartialEq, Eq)]
+pub enum SortError {
+    /// The table has no column with the requested name.
+    #[snafu(display("column {column} is not in the table"))]
+    ColumnNotFound {
+        /// The requested column name
+        column: String,
+    },
+    /// Rows cannot be ordered by a column of this type.
+    #[snafu(display("cannot sort by a column of type {column_type}"))]
+    UnsupportedColumnType {
+        /// The type of the column
+        column_type: ColumnType,
+    },
+}
+
+impl DynOwnedTable {
+    /// Reorder the rows of the table by the values of `column`, matched ignoring case.
+    ///
+    /// This is a display convenience for verified results and has no bearing on verification.
+    /// Decimals and scalars are ordered as signed values, and rows with equal values keep their
+    /// relative order.
+    pub fn sorted_by(&self, column: &str, ascending: bool) -> Result<DynOwnedTable, SortError> {
+        match self {
+            DynOwnedTable::Dory(table) => {
+                sort_table(table, column, ascending).map(DynOwnedTable::Dory)
+            }
+            #[cfg(feature = "hyperkzg")]
+            DynOwnedTable::BN(table) => sort_table(table, column, ascending).map(DynOwnedTable::BN),
+        }
+    }
+}
+
+fn sort_table<S: Scalar>(
+    table: &OwnedTable<S>,
+    column: &str,
+    ascending: bool,
+) -> Result<OwnedTable<S>, SortError> {
+    let (_, sort_column) = table
+        .inner_table()
+        .iter()
+        .find(|(ident, _)| ident.value.eq_ignore_ascii_case(column))
+        .context(ColumnNotFoundSnafu { column })?;
+    let mut indexes: Vec<usize> = (0..table.num_rows()).collect();
+    sort_indexes(&mut indexes, sort_column, ascending)?;
+    let columns = table
+        .inner_table()
+        .iter()
+        .map(|(ident, column)| Ok((ident.clone(), permute_column(column, &indexes)?)))
+        .collect::<Result<IndexMap<_, _>, SortError>>()?;
+    Ok(OwnedTable::try_new(columns).expect("permuted columns have the same length"))
+}
+
+/// Orders scalars as signed values, where values above `MAX_SIGNED` are negative.
+fn signed_cmp<S: Scalar>(a: &S, b: &S) -> Ordering {
+    (*a <= S::MAX_SIGNED)
+        .cmp(&(*b <= S::MAX_SIGNED))
+        .then_with(|| a.cmp(b))
+}
+
+fn sort_indexes<S: Scalar>(
+    indexes: &mut [usize],
+    column: &OwnedColumn<S>,
+    ascending: bool,
+) -> Result<(), SortError> {
+    fn sort_by_values<T>(
+        indexes: &mut [usize],
+        values: &[T],
+        cmp: impl Fn(&T, &T) -> Ordering,
+        ascending: bool,
+    ) {
+        indexes.sort_by(|&i, &j| {
+            let ordering = cmp(&values[i], &values[j]);
+            if ascending {
+                ordering
+            } else {
+                ordering.reverse()
+            }
+        });
+    }
+
+    match column {
+        OwnedColumn::Boolean(values) => sort_by_values(indexes, values, Ord::cmp, ascending),
+        OwnedColumn::Uint8(values) => sort_by_values(indexes, values, Ord::cmp, ascending),
+        OwnedColumn::TinyInt(values) => sort_by_values(indexes, values, Ord::cmp, ascending),
+        OwnedColumn::SmallInt(values) => sort_by_values(indexes, values, Ord::cmp, ascending),
+        OwnedColumn::Int(values) => sort_by_values(indexes, values, Ord::cmp, ascending),
+        OwnedColumn::BigInt(values) | OwnedColumn::TimestampTZ(_, _, values) => {
+            sort_by_values(indexes, values, Ord::cmp, ascending);
+        }
+        OwnedColumn::Int128(values) => sort_by_values(indexes, values, Ord::cmp, ascending),
+        OwnedColumn::VarChar(values) => sort_by_values(indexes, values, Ord::cmp, ascending),
+        OwnedColumn::VarBinary(values) => sort_by_values(indexes, values, Ord::cmp, ascending),
+        OwnedColumn::Decimal75(_, _, values) | OwnedColumn::Scalar(values) => {
+            sort_by_values(indexes, values, signed_cmp, ascending);
+        }
+        _ => {
+            return UnsupportedColumnTypeSnafu {
+                column_type: column.column_type(),
+            }
+            .fail()
+        }
+    }
+    Ok(())
+}
+
+fn permute_column<S: Scalar>(
+    column: &OwnedColumn<S>,
+    indexes: &[usize],
+) -> Result<OwnedColumn<S>, SortError> {
+    fn permute<T: Clone>(values: &[T], indexes: &[usize]) -> Vec<T> {
+        indexes.iter().map(|&i| values[i].clone()).collect()
+    }
+
+    Ok(match column {
+        OwnedColumn::Boolean(values) => OwnedColumn::Boolean(permute(values, indexes)),
+        OwnedColumn::Uint8(values) => OwnedColumn::Uint8(permute(values, indexes)),
+        OwnedColumn::TinyInt(values) => OwnedColumn::TinyInt(permute(values, indexes)),
+        OwnedColumn::SmallInt(values) => OwnedColumn::SmallInt(permute(values, indexes)),
+        OwnedColumn::Int(values) => OwnedColumn::Int(permute(values, indexes)),
+        OwnedColumn::BigInt(values) => OwnedColumn::BigInt(permute(values, indexes)),
+        OwnedColumn::Int128(values) => OwnedColumn::Int128(permute(values, indexes)),
+        OwnedColumn::VarChar(values) => OwnedColumn::VarChar(permute(values, indexes)),
+        OwnedColumn::VarBinary(values) => OwnedColumn::VarBinary(permute(values, indexes)),
+        OwnedColumn::Decimal75(precision, scale, values) => {
+            OwnedColumn::Decimal75(*precision, *scale, permute(values, indexes))
+        }
+        OwnedColumn::Scalar(values) => OwnedColumn::Scalar(permute(values, indexes)),
+        OwnedColumn::TimestampTZ(time_unit, time_zone, values) => {
+            OwnedColumn::TimestampTZ(*time_unit, *time_zone, permute(values, indexes))
+        }
+        _ => {
+            return UnsupportedColumnTypeSnafu {
+                column_type: column.column_type(),
+            }
+            .fail()
+        }
+    })
+}
+
 impl TryFrom<DynOwnedTable> for RecordBatch {
     type Error = ArrowError;
 
@@ -25,3 +181,178 @@ impl TryFrom<DynOwnedTable> for RecordBatch {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proof_of_sql::base::math::decimal::Precision;
+    use sqlparser::ast::Ident;
+
+    fn owned_table<S: Scalar>() -> OwnedTable<S> {
+        OwnedTable::try_new(
+            [
+                (
+                    Ident::new("BLOCK_NUMBER"),
+                    OwnedColumn::BigInt(vec![22432845, 22432846]),
+                ),
+                (
+                    Ident::new("MINER"),
+                    OwnedColumn::VarChar(vec!["a".to_string(), "b".to_string()]),
+                ),
+            ]
+            .into_iter()
+            .collect(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn we_can_round_trip_a_dory_table_through_serde() {
+        let table = DynOwnedTable::Dory(owned_table());
+        let json = serde_json::to_value(&table).unwrap();
+        assert_eq!(json["scheme"], "DynamicDory");
+        let deserialized: DynOwnedTable = serde_json::from_value(json).unwrap();
+        assert!(matches!(deserialized, DynOwnedTable::Dory(_)));
+        assert_eq!(deserialized, table);
+    }
+
+    #[cfg(feature = "hyperkzg")]
+    #[test]
+    fn we_can_round_trip_a_hyperkzg_table_through_serde() {
+        let table = DynOwnedTable::BN(owned_table());
+        let json = serde_json::to_value(&table).unwrap();
+        assert_eq!(json["scheme"], "HyperKzg");
+        let deserialized: DynOwnedTable = serde_json::from_value(json).unwrap();
+        assert!(matches!(deserialized, DynOwnedTable::BN(_)));
+        assert_eq!(deserialized, table);
+    }
+
+    fn empty_table<S: Scalar>() -> OwnedTable<S> {
+        OwnedTable::try_new(
+            [
+                (Ident::new("BLOCK_NUMBER"), OwnedColumn::BigInt(vec![])),
+                (Ident::new("MINER"), OwnedColumn::VarChar(vec![])),
+            ]
+            .into_iter()
+            .collect(),
+        )
+        .unwrap()
+    }
+
+    fn assert_is_empty_with_columns(record_batch: RecordBatch) {
+        use datafusion::arrow::datatypes::DataType;
+
+        assert_eq!(record_batch.num_rows(), 0);
+        assert_eq!(
+            record_batch
+                .schema()
+                .fields()
+                .iter()
+                .map(|field| (field.name().clone(), field.data_type().clone()))
+                .collect::<Vec<_>>(),
+            vec![
+                ("BLOCK_NUMBER".to_string(), DataType::Int64),
+                ("MINER".to_string(), DataType::Utf8),
+            ]
+        );
+    }
+
+    #[test]
+    fn we_keep_the_columns_of_an_empty_dory_table() {
+        assert_is_empty_with_columns(
+            RecordBatch::try_from(DynOwnedTable::Dory(empty_table())).unwrap(),
+        );
+    }
+
+    #[cfg(feature = "hyperkzg")]
+    #[test]
+    fn we_keep_the_columns_of_an_empty_hyperkzg_table() {
+        assert_is_empty_with_columns(
+            RecordBatch::try_from(DynOwnedTable::BN(empty_table())).unwrap(),
+        );
+    }
+
+    fn mixed_table() -> DynOwnedTable {
+        DynOwnedTable::Dory(
+            OwnedTable::try_new(
+                [
+                    (Ident::new("ID"), OwnedColumn::BigInt(vec![1, 2, 3, 4])),
+                    (
+                        Ident::new("NAME"),
+                        OwnedColumn::VarChar(vec![
+                            "d".to_string(),
+                            "b".to_string(),
+                            "a".to_string(),
+                            "c".to_string(),
+                        ]),
+                    ),
+                    (
+                        Ident::new("AMOUNT"),
+                        OwnedColumn::Decimal75(
+                            Precision::new(10).unwrap(),
+                            2,
+                            [150, -25, 0, -300].map(DoryScalar::from).to_vec(),
+                        ),
+                    ),
+                ]
+                .into_iter()
+                .collect(),
+            )
+            .unwrap(),
+        )
+    }
+
+    fn ids(table: DynOwnedTable) -> OwnedColumn<DoryScalar> {
+        let DynOwnedTable::Dory(table) = table else {
+            panic!("expected a Dory table");
+        };
+        table.inner_table()[&Ident::new("ID")].clone()
+    }
+
+    #[test]
+    fn we_can_sort_a_mixed_type_table_ascending() {
+        let table = mixed_table();
+        assert_eq!(
+            ids(table.sorted_by("NAME", true).unwrap()),
+            OwnedColumn::BigInt(vec![3, 2, 4, 1])
+        );
+        // Negative decimals sort below zero rather than as large field elements
+        assert_eq!(
+            ids(table.sorted_by("amount", true).unwrap()),
+            OwnedColumn::BigInt(vec![4, 2, 3, 1])
+        );
+    }
+
+    #[test]
+    fn we_can_sort_a_mixed_type_table_descending() {
+        let table = mixed_table();
+        let sorted = table.sorted_by("AMOUNT", false).unwrap();
+        let DynOwnedTable::Dory(sorted_table) = &sorted else {
+            panic!("expected a Dory table");
+        };
+        assert_eq!(
+            sorted_table.inner_table()[&Ident::new("NAME")],
+            OwnedColumn::VarChar(vec![
+                "d".to_string(),
+                "a".to_string(),
+                "b".to_string(),
+                "c".to_string()
+            ])
+        );
+        assert_eq!(ids(sorted), OwnedColumn::BigInt(vec![1, 3, 2, 4]));
+        assert_eq!(
+            ids(table.sorted_by("ID", false).unwrap()),
+            OwnedColumn::BigInt(vec![4, 3, 2, 1])
+        );
+    }
+
+    #[test]
+    fn we_cannot_sort_by_a_missing_column() {
+        assert_eq!(
+            mixed_table().sorted_by("MISSING", true),
+            Err(SortError::ColumnNotFound {
+                column: "MISSING".to_string()
+            })
+        );
+    }
+}
@@ -0,0 +1,67 @@
+use crate::base::{attestation::AttestationError, ParseSqlError};
+use datafusion::arrow::error::ArrowError;
+use snafu::Snafu;
+
+/// Errors that can occur when querying and verifying through [`super::SxTClient`].
+///
+/// Each variant keeps the underlying error as its source, so the full cause chain is available
+/// through [`core::error::Error::source`]. `SdkError` converts into `Box<dyn Error>` for callers
+/// that do not need to distinguish failures.
+#[derive(Debug, Snafu)]
+#[snafu(visibility(pub(crate)))]
+pub enum SdkError {
+    /// The query is not a single valid SQL statement.
+    #[snafu(display("invalid query: {source}"), context(false))]
+    InvalidQuery {
+        /// Source of the error.
+        source: ParseSqlError,
+    },
+    /// The query parameters could not be serialized.
+    #[snafu(display("invalid query parameters: {source}"), context(false))]
+    InvalidParams {
+        /// Source of the error.
+        source: bincode::error::EncodeError,
+    },
+    /// The verifier setup could not be read or deserialized.
+    #[snafu(display("unable to load verifier setup: {source}"))]
+    VerifierSetup {
+        /// Source of the error.
+        source: Box<dyn core::error::Error>,
+    },
+    /// An access token could not be obtained from the auth service.
+    #[snafu(display("unable to authenticate: {source}"))]
+    Authentication {
+        /// Source of the error.
+        source: Box<dyn core::error::Error>,
+    },
+    /// The request to the ZK Query API failed.
+    #[snafu(display("ZK Query API request failed: {source}"))]
+    ZkQueryApi {
+        /// Source of the error.
+        source: Box<dyn core::error::Error>,
+    },
+    /// The ZK Query API ran the query but reported a failure.
+    #[snafu(display("ZK query failed: {message}"))]
+    QueryFailed {
+        /// Error message reported by the ZK Query API.
+        message: String,
+    },
+    /// The attested commitments could not be verified.
+    #[snafu(display("attestation verification failed: {source}"), context(false))]
+    Attestation {
+        /// Source of the error.
+        source: AttestationError,
+    },
+    /// The proof or the query result could not be verified.
+    #[snafu(display("verification failed: {source}"))]
+    Verification {
+        /// Source of the error.
+        source: Box<dyn core::error::Error>,
+    },
+    /// The verified result could not be converted into record batches.
+    #[snafu(display("unable to convert verified result: {source}"), context(false))]
+    ResultConversion {
+        /// Source of the error.
+        source: ArrowError,
+    },
+}
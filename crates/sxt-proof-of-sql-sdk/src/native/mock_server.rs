@@ -0,0 +1,166 @@
+//! A minimal HTTP server for testing code that talks to the auth service and the ZK Query API.
+#[cfg(feature = "hyperkzg")]
+use crate::{
+    base::{zk_query_models::SxtNetwork, CommitmentScheme},
+    query_and_verify::{OutputFormat, QueryAndVerifySdkArgs},
+};
+#[cfg(feature = "hyperkzg")]
+use std::path::PathBuf;
+use std::{
+    io::{BufRead, BufReader, Read, Write},
+    net::TcpListener,
+    sync::{Arc, Mutex},
+};
+use url::Url;
+
+/// Request line and body of every request received by a mock server.
+pub(crate) type RecordedRequests = Arc<Mutex<Vec<(String, String)>>>;
+
+/// Starts a local HTTP server that answers requests for the given paths with canned JSON
+/// bodies, and 404 otherwise. The request line and body of every request received are recorded.
+///
+/// A path may be listed more than once to answer successive requests differently. The last body
+/// listed for a path keeps answering once the others are used up.
+pub(crate) fn mock_server(routes: Vec<(&'static str, String)>) -> (Url, RecordedRequests) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = Url::parse(&format!("http://{}", listener.local_addr().unwrap())).unwrap();
+    let requests = Arc::new(Mutex::new(Vec::new()));
+    let recorded_requests = requests.clone();
+    std::thread::spawn(move || {
+        let mut hits: Vec<String> = Vec::new();
+        for stream in listener.incoming() {
+            let mut stream = stream.unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut request_line = String::new();
+            reader.read_line(&mut request_line).unwrap();
+            // Read the headers, which end with an empty line, keeping the body length
+            let mut content_length = 0;
+            let mut header = String::new();
+            while reader.read_line(&mut header).unwrap() > 2 {
+                if let Some((name, value)) = header.split_once(':') {
+                    if name.eq_ignore_ascii_case("content-length") {
+                        content_length = value.trim().parse().unwrap();
+                    }
+                }
+                header.clear();
+            }
+            let mut body = vec![0; content_length];
+            reader.read_exact(&mut body).unwrap();
+            let request_line = request_line.trim_end().to_string();
+            let path = request_line.split(' ').nth(1).unwrap_or_default();
+            let previous_hits = hits.iter().filter(|hit| *hit == path).count();
+            let matching_routes: Vec<_> =
+                routes.iter().filter(|(route, _)| *route == path).collect();
+            hits.push(path.to_string());
+            let route = matching_routes
+                .get(previous_hits)
+                .or(matching_routes.last())
+                .copied();
+            let response = match route {
+                Some((_, body)) => format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                    body.len()
+                ),
+                None => "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+                    .to_string(),
+            };
+            recorded_requests
+                .lock()
+                .unwrap()
+                .push((request_line, String::from_utf8(body).unwrap()));
+            stream.write_all(response.as_bytes()).unwrap();
+        }
+    });
+    (url, requests)
+}
+
+pub(crate) fn request_lines(requests: &Mutex<Vec<(String, String)>>) -> Vec<String> {
+    requests
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|(request_line, _)| request_line.clone())
+        .collect()
+}
+
+/// A HyperKZG ZK Query API response for `ETHEREUM.BLOCKS`.
+#[cfg(feature = "hyperkzg")]
+pub(crate) const FIXTURE: &str =
+    include_str!("../../../../test_assets/valid_gateway_response.json");
+
+/// Routes for an auth service rooted at `/proxy` and a ZK Query API that plans and runs every
+/// query as the [`FIXTURE`] query.
+#[cfg(feature = "hyperkzg")]
+pub(crate) fn fixture_routes() -> Vec<(&'static str, String)> {
+    let fixture: serde_json::Value = serde_json::from_str(FIXTURE).unwrap();
+    let query_id = fixture["queryId"].as_str().unwrap();
+    vec![
+        (
+            "/proxy/auth/apikey",
+            r#"{"accessToken":"token"}"#.to_string(),
+        ),
+        (
+            "/v1/zkquery/build-plan",
+            format!(r#"{{"plan":"{}"}}"#, fixture["plan"].as_str().unwrap()),
+        ),
+        (
+            "/v1/zkquery",
+            format!(
+                r#"{{"queryId":"{query_id}","created":"2026-03-19T04:19:10.438548Z","commitmentScheme":"HYPER_KZG"}}"#
+            ),
+        ),
+        (
+            "/v1/zkquery/060e98dd-63a3-40f3-9ca3-cf2451b9bd36/status",
+            format!(
+                r#"{{"queryId":"{query_id}","created":"2026-03-19T04:19:10.438548Z","commitmentScheme":"HYPER_KZG","status":"done"}}"#
+            ),
+        ),
+        (
+            "/v1/zkquery/060e98dd-63a3-40f3-9ca3-cf2451b9bd36/results",
+            FIXTURE.to_string(),
+        ),
+    ]
+}
+
+/// Arguments for running `query` with HyperKZG against an auth service rooted at `/proxy` and a
+/// ZK Query API at `base_url`, writing a table to stdout.
+#[cfg(feature = "hyperkzg")]
+pub(crate) fn query_and_verify_args(base_url: &Url, query: &str) -> QueryAndVerifySdkArgs {
+    QueryAndVerifySdkArgs {
+        network: SxtNetwork::Mainnet,
+        zk_query_root_url: base_url.clone(),
+        auth_root_url: base_url.join("proxy").unwrap(),
+        sxt_api_key: "key".to_string(),
+        query: query.to_string(),
+        block_hash: None,
+        commitment_scheme: CommitmentScheme::HyperKzg,
+        verifier_setup: None,
+        csv_file_path: None,
+        parquet_file_path: None,
+        source_network: SxtNetwork::Mainnet,
+        explain: false,
+        explain_only: false,
+        limit_rows: None,
+        retry_on_failed: 0,
+        output_format: OutputFormat::Table,
+        quiet: false,
+    }
+}
+
+/// Writes the [`FIXTURE`] with the last hex digit of its `field` flipped to a file in the temp
+/// directory and returns the path. The caller removes the file.
+#[cfg(feature = "hyperkzg")]
+pub(crate) fn tampered_fixture_file(field: &str) -> PathBuf {
+    let mut response: serde_json::Value = serde_json::from_str(FIXTURE).unwrap();
+    let mut value = response[field].as_str().unwrap().to_string();
+    let last = value.pop().unwrap();
+    value.push(if last == '0' { '1' } else { '0' });
+    response[field] = value.into();
+
+    let path = std::env::temp_dir().join(format!(
+        "gateway_response_with_tampered_{field}_{}.json",
+        std::process::id()
+    ));
+    std::fs::write(&path, response.to_string()).unwrap();
+    path
+}
@@ -1,15 +1,29 @@
 mod auth;
-pub use auth::get_access_token;
+pub use auth::{get_access_token, get_access_token_with_scope, AuthScope};
 
-mod dory_commitment_scheme;
+mod error;
+pub use error::SdkError;
 
 mod dyn_owned_table;
+pub use dyn_owned_table::{DynOwnedTable, SortError};
 
 mod plan;
-pub use plan::produce_plan;
+pub use plan::{produce_plan, UnboundPlaceholderError};
+
+mod table_schema;
+pub use table_schema::TableSchema;
+
+mod result_cache;
+pub use result_cache::{ResultCache, ResultCacheKey};
 
 mod client;
-pub use client::SxTClient;
+pub use client::{CheckedResult, SxTClient, DEFAULT_MIN_ATTESTATIONS, MAX_BATCH_CONCURRENCY};
 
 mod zk_query_client;
-pub use zk_query_client::ZkQueryClient;
+pub use zk_query_client::{
+    PollStrategy, ZkQueryClient, ZkQueryClientError, DEFAULT_MAX_RESPONSE_BYTES,
+    DEFAULT_REQUEST_TIMEOUT,
+};
+
+#[cfg(test)]
+pub(crate) mod mock_server;
@@ -1,34 +1,69 @@
 use super::{get_access_token, ZkQueryClient};
-use crate::base::zk_query_models::{QueryPlanRequest, SxtNetwork};
-use proof_of_sql::{base::try_standard_binary_deserialization, sql::evm_proof_plan::EVMProofPlan};
-use reqwest::Client;
+use crate::base::{
+    parse_single_statement,
+    zk_query_models::{serialize_query_params, QueryPlanRequest, SxtNetwork},
+};
+use core::ops::ControlFlow;
+use proof_of_sql::{
+    base::{database::LiteralValue, try_standard_binary_deserialization},
+    sql::evm_proof_plan::EVMProofPlan,
+};
+use snafu::Snafu;
+use sqlparser::ast::{visit_expressions, Expr, Statement, Value};
 use url::Url;
 
+/// Error returned by [`produce_plan`] when the query has placeholders but no params were supplied.
+#[derive(Snafu, Debug, PartialEq, Eq)]
+#[snafu(display("query placeholder {placeholder} is not bound to a parameter"))]
+pub struct UnboundPlaceholderError {
+    /// The first placeholder found in the query, e.g. `$1`
+    placeholder: String,
+}
+
+/// Find the first placeholder (e.g. `$1` or `?`) in a statement.
+fn find_placeholder(statement: &Statement) -> Option<String> {
+    match visit_expressions(statement, |expr| match expr {
+        Expr::Value(Value::Placeholder(placeholder)) => ControlFlow::Break(placeholder.clone()),
+        _ => ControlFlow::Continue(()),
+    }) {
+        ControlFlow::Break(placeholder) => Some(placeholder),
+        ControlFlow::Continue(()) => None,
+    }
+}
+
 /// Produces a plan given the API parameters and the query
 ///
-/// This function uses the ZK Query API to build a proof plan
+/// This function uses the ZK Query API to build a proof plan. The `params` are sent along with
+/// the query so that the plan is built for their types; a query with placeholders is rejected if
+/// `params` is empty.
 pub async fn produce_plan(
     zk_query_root_url: Url,
     auth_root_url: Url,
     api_key: &str,
     query: &str,
+    params: &[LiteralValue],
     source_network: SxtNetwork,
 ) -> Result<EVMProofPlan, Box<dyn core::error::Error>> {
+    // Reject empty and multi-statement input before contacting the API
+    let statement = parse_single_statement(query)?;
+    if params.is_empty() {
+        if let Some(placeholder) = find_placeholder(&statement) {
+            return Err(UnboundPlaceholderError { placeholder }.into());
+        }
+    }
+
     // Get access token
     let access_token = get_access_token(api_key, auth_root_url.as_str()).await?;
 
     // Create ZkQueryClient
-    let client = ZkQueryClient {
-        base_url: zk_query_root_url.clone(),
-        client: Client::new(),
-        access_token,
-    };
+    let client = ZkQueryClient::new(zk_query_root_url.clone(), access_token);
 
     // Create request
     let request = QueryPlanRequest {
-        sql_text: query.to_string(),
-        source_network,
-        evm_compatible: true,
+        params: (!params.is_empty())
+            .then(|| serialize_query_params(params))
+            .transpose()?,
+        ..QueryPlanRequest::new(query.to_string(), source_network, None)
     };
 
     // Get plan from API
@@ -42,3 +77,86 @@ pub async fn produce_plan(
 
     Ok(plan)
 }
+
+#[cfg(test)]
+#[cfg(feature = "hyperkzg")]
+mod tests {
+    use super::*;
+    use crate::native::mock_server::{fixture_routes, mock_server};
+
+    const PARAMETERIZED_QUERY: &str = "SELECT * FROM ETHEREUM.BLOCKS WHERE BLOCK_NUMBER = $1";
+
+    #[tokio::test]
+    async fn we_can_produce_a_plan_for_a_parameterized_query() {
+        let (base_url, requests) = mock_server(fixture_routes());
+        let params = [LiteralValue::BigInt(22432845)];
+
+        produce_plan(
+            base_url.clone(),
+            base_url.join("proxy").unwrap(),
+            "api-key",
+            PARAMETERIZED_QUERY,
+            &params,
+            SxtNetwork::Mainnet,
+        )
+        .await
+        .unwrap();
+
+        let requests = requests.lock().unwrap();
+        let (_, body) = requests
+            .iter()
+            .find(|(line, _)| line.starts_with("POST /v1/zkquery/build-plan "))
+            .unwrap();
+        let body: serde_json::Value = serde_json::from_str(body).unwrap();
+        assert_eq!(body["sqlText"], PARAMETERIZED_QUERY);
+        assert_eq!(body["params"], serialize_query_params(&params).unwrap());
+    }
+
+    #[tokio::test]
+    async fn we_do_not_send_params_for_a_query_without_placeholders() {
+        let (base_url, requests) = mock_server(fixture_routes());
+
+        produce_plan(
+            base_url.clone(),
+            base_url.join("proxy").unwrap(),
+            "api-key",
+            "SELECT * FROM ETHEREUM.BLOCKS",
+            &[],
+            SxtNetwork::Mainnet,
+        )
+        .await
+        .unwrap();
+
+        let requests = requests.lock().unwrap();
+        let (_, body) = requests
+            .iter()
+            .find(|(line, _)| line.starts_with("POST /v1/zkquery/build-plan "))
+            .unwrap();
+        let body: serde_json::Value = serde_json::from_str(body).unwrap();
+        assert!(body.get("params").is_none());
+    }
+
+    #[tokio::test]
+    async fn we_cannot_produce_a_plan_with_unbound_placeholders() {
+        let (base_url, requests) = mock_server(fixture_routes());
+
+        let err = produce_plan(
+            base_url.clone(),
+            base_url.join("proxy").unwrap(),
+            "api-key",
+            PARAMETERIZED_QUERY,
+            &[],
+            SxtNetwork::Mainnet,
+        )
+        .await
+        .unwrap_err();
+
+        assert_eq!(
+            err.downcast_ref::<UnboundPlaceholderError>(),
+            Some(&UnboundPlaceholderError {
+                placeholder: "$1".to_string()
+            })
+        );
+        assert!(requests.lock().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,170 @@
+use super::DynOwnedTable;
+use crate::base::CommitmentScheme;
+use indexmap::IndexMap;
+use std::{
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+/// Key identifying a verified query result.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ResultCacheKey {
+    /// The query in its normalized (parsed and re-rendered) form
+    pub normalized_query: String,
+    /// The hex encoded query parameters
+    pub params: String,
+    /// The block hash the query was run against
+    pub block_hash: [u8; 32],
+    /// The commitment scheme the result was verified with
+    pub commitment_scheme: CommitmentScheme,
+    /// Whether the attestations were skipped when the result was verified
+    pub skip_attestation_verification: bool,
+    /// The number of distinct attestors the result was required to have
+    pub min_attestations: usize,
+}
+
+/// In-memory LRU cache of verified query results.
+///
+/// Entries expire `ttl` after insertion, and the least recently used entry is evicted once the
+/// cache holds `capacity` entries.
+#[derive(Debug)]
+pub struct ResultCache {
+    capacity: usize,
+    ttl: Duration,
+    entries: Mutex<IndexMap<ResultCacheKey, (Instant, DynOwnedTable)>>,
+}
+
+impl ResultCache {
+    /// Create an empty cache.
+    pub fn new(capacity: usize, ttl: Duration) -> Self {
+        Self {
+            capacity,
+            ttl,
+            entries: Mutex::new(IndexMap::new()),
+        }
+    }
+
+    /// Get a cached result if one exists and has not expired.
+    pub fn get(&self, key: &ResultCacheKey) -> Option<DynOwnedTable> {
+        let mut entries = self.entries.lock().expect("result cache lock poisoned");
+        let (inserted, table) = entries.shift_remove(key)?;
+        if inserted.elapsed() >= self.ttl {
+            return None;
+        }
+        // Re-insert at the back to mark the entry as most recently used
+        entries.insert(key.clone(), (inserted, table.clone()));
+        Some(table)
+    }
+
+    /// Cache a verified result, evicting the least recently used entry if the cache is full.
+    pub fn insert(&self, key: ResultCacheKey, table: DynOwnedTable) {
+        if self.capacity == 0 {
+            return;
+        }
+        let mut entries = self.entries.lock().expect("result cache lock poisoned");
+        entries.shift_remove(&key);
+        while entries.len() >= self.capacity {
+            entries.shift_remove_index(0);
+        }
+        entries.insert(key, (Instant::now(), table));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proof_of_sql::base::database::{OwnedColumn, OwnedTable};
+    use sqlparser::ast::Ident;
+
+    fn key(block_hash: [u8; 32]) -> ResultCacheKey {
+        ResultCacheKey {
+            normalized_query: "SELECT * FROM ETHEREUM.BLOCKS".to_string(),
+            params: "0x00".to_string(),
+            block_hash,
+            commitment_scheme: CommitmentScheme::DynamicDory,
+            skip_attestation_verification: false,
+            min_attestations: 1,
+        }
+    }
+
+    fn table(value: i64) -> DynOwnedTable {
+        DynOwnedTable::Dory(
+            OwnedTable::try_new(
+                [(Ident::new("A"), OwnedColumn::BigInt(vec![value]))]
+                    .into_iter()
+                    .collect(),
+            )
+            .unwrap(),
+        )
+    }
+
+    #[test]
+    fn we_can_hit_a_cached_result() {
+        let cache = ResultCache::new(2, Duration::from_secs(60));
+        cache.insert(key([1; 32]), table(1));
+        assert_eq!(cache.get(&key([1; 32])), Some(table(1)));
+    }
+
+    #[test]
+    fn we_miss_for_a_different_key() {
+        let cache = ResultCache::new(2, Duration::from_secs(60));
+        cache.insert(key([1; 32]), table(1));
+        assert_eq!(cache.get(&key([2; 32])), None);
+        assert_eq!(
+            cache.get(&ResultCacheKey {
+                normalized_query: "SELECT * FROM ETHEREUM.TRANSACTIONS".to_string(),
+                ..key([1; 32])
+            }),
+            None
+        );
+        assert_eq!(
+            cache.get(&ResultCacheKey {
+                params: "0x0100".to_string(),
+                ..key([1; 32])
+            }),
+            None
+        );
+    }
+
+    #[test]
+    fn we_miss_for_a_result_verified_with_different_attestation_checks() {
+        let cache = ResultCache::new(2, Duration::from_secs(60));
+        cache.insert(
+            ResultCacheKey {
+                skip_attestation_verification: true,
+                ..key([1; 32])
+            },
+            table(1),
+        );
+        assert_eq!(cache.get(&key([1; 32])), None);
+
+        cache.insert(key([1; 32]), table(1));
+        assert_eq!(
+            cache.get(&ResultCacheKey {
+                min_attestations: 3,
+                ..key([1; 32])
+            }),
+            None
+        );
+    }
+
+    #[test]
+    fn we_miss_for_an_expired_result() {
+        let cache = ResultCache::new(2, Duration::ZERO);
+        cache.insert(key([1; 32]), table(1));
+        assert_eq!(cache.get(&key([1; 32])), None);
+    }
+
+    #[test]
+    fn we_evict_the_least_recently_used_result() {
+        let cache = ResultCache::new(2, Duration::from_secs(60));
+        cache.insert(key([1; 32]), table(1));
+        cache.insert(key([2; 32]), table(2));
+        assert!(cache.get(&key([1; 32])).is_some());
+        cache.insert(key([3; 32]), table(3));
+
+        assert_eq!(cache.get(&key([2; 32])), None);
+        assert_eq!(cache.get(&key([1; 32])), Some(table(1)));
+        assert_eq!(cache.get(&key([3; 32])), Some(table(3)));
+    }
+}
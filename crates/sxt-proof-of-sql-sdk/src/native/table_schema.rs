@@ -0,0 +1,33 @@
+use indexmap::IndexMap;
+use proof_of_sql::base::{
+    commitment::{Commitment, TableCommitment},
+    database::{ColumnType, TableRef},
+};
+use sqlparser::ast::Ident;
+
+/// The columns of a table, as recorded in its attested commitment.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TableSchema {
+    /// The table described
+    pub table_ref: TableRef,
+    /// The names and types of the columns, in table order
+    pub columns: IndexMap<Ident, ColumnType>,
+}
+
+impl TableSchema {
+    /// Read the schema of `table_ref` from the column metadata of its commitment.
+    pub fn from_table_commitment<C: Commitment>(
+        table_ref: TableRef,
+        table_commitment: &TableCommitment<C>,
+    ) -> Self {
+        Self {
+            table_ref,
+            columns: table_commitment
+                .column_commitments()
+                .column_metadata()
+                .iter()
+                .map(|(ident, metadata)| (ident.clone(), *metadata.column_type()))
+                .collect(),
+        }
+    }
+}
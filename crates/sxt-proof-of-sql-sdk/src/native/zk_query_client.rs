@@ -1,14 +1,103 @@
 //! Client for interacting with the ZK Query APIs
 use crate::base::zk_query_models::{
-    QueryPlanRequest, QueryPlanResponse, QueryResultsResponse, QueryStatusResponse,
-    QuerySubmitRequest, QuerySubmitResponse, ZkQueryStatus,
+    QueryListResponse, QueryPlanRequest, QueryPlanResponse, QueryResultsResponse,
+    QueryStatusResponse, QuerySubmitRequest, QuerySubmitResponse, ZkQueryStatus,
 };
-use reqwest::Client;
-use std::{future::Future, pin::Pin};
+use reqwest::{Client, RequestBuilder};
+use snafu::Snafu;
+use std::time::Duration;
 use url::Url;
 
 const INITIAL_MILLISECONDS_TO_RETRY: u64 = 10;
 const MAX_MILLISECONDS_TO_RETRY: u64 = 1_800_000;
+const INITIAL_MILLISECONDS_TO_RESUBMIT: u64 = 1_000;
+
+/// Default time to wait for a single request to the ZK Query API.
+pub const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Default cap on the size of a single response body from the ZK Query API.
+///
+/// Proofs and commitments are well below this, so the default only rejects responses that are
+/// clearly malicious or broken.
+pub const DEFAULT_MAX_RESPONSE_BYTES: usize = 1024 * 1024 * 1024;
+
+/// Number of polls [`PollStrategy::Adaptive`] makes at its fast interval before backing off.
+const ADAPTIVE_FAST_POLLS: u32 = 20;
+const ADAPTIVE_FAST_INTERVAL: Duration = Duration::from_millis(50);
+const ADAPTIVE_INITIAL_BACKOFF: Duration = Duration::from_millis(100);
+
+/// How [`ZkQueryClient`] waits between polls of the status of a running query.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PollStrategy {
+    /// Wait `initial` after the first poll and double the wait after every further poll, up to
+    /// `max`.
+    Exponential {
+        /// Wait after the first poll
+        initial: Duration,
+        /// Longest wait between two polls
+        max: Duration,
+    },
+    /// Wait the same interval between every two polls, e.g. to notice fast test queries finish
+    /// quickly.
+    Fixed(Duration),
+    /// Poll every 50 milliseconds for about the first second, then back off exponentially from
+    /// 100 milliseconds. Fast queries are noticed quickly without polling slow ones too often.
+    Adaptive,
+}
+
+/// Exponential backoff from 10 milliseconds up to 30 minutes.
+impl Default for PollStrategy {
+    fn default() -> Self {
+        PollStrategy::Exponential {
+            initial: Duration::from_millis(INITIAL_MILLISECONDS_TO_RETRY),
+            max: Duration::from_millis(MAX_MILLISECONDS_TO_RETRY),
+        }
+    }
+}
+
+impl PollStrategy {
+    /// How long to wait after the `poll`th poll, counting from zero, before polling again.
+    pub fn delay(self, poll: u32) -> Duration {
+        match self {
+            PollStrategy::Exponential { initial, max } => 2u32
+                .checked_pow(poll)
+                .and_then(|factor| initial.checked_mul(factor))
+                .map_or(max, |delay| delay.min(max)),
+            PollStrategy::Fixed(interval) => interval,
+            PollStrategy::Adaptive if poll < ADAPTIVE_FAST_POLLS => ADAPTIVE_FAST_INTERVAL,
+            PollStrategy::Adaptive => PollStrategy::Exponential {
+                initial: ADAPTIVE_INITIAL_BACKOFF,
+                max: Duration::from_millis(MAX_MILLISECONDS_TO_RETRY),
+            }
+            .delay(poll - ADAPTIVE_FAST_POLLS),
+        }
+    }
+}
+
+/// Errors raised by the [`ZkQueryClient`] itself rather than reported by the ZK Query API.
+#[derive(Snafu, Debug)]
+pub enum ZkQueryClientError {
+    /// A single HTTP request took longer than the client's `request_timeout`.
+    ///
+    /// This is distinct from the `timeout` of a [`QuerySubmitRequest`], which bounds how long the
+    /// query itself may run.
+    #[snafu(display("request to the ZK Query API timed out after {timeout:?}"))]
+    RequestTimeout {
+        /// The request timeout that was exceeded
+        timeout: Duration,
+    },
+    /// A response body was larger than the client's `max_response_bytes`.
+    ///
+    /// The body is rejected as soon as its announced or received length exceeds the cap, so an
+    /// oversized response is never buffered in full.
+    #[snafu(display(
+        "response from the ZK Query API exceeds the maximum of {max_response_bytes} bytes"
+    ))]
+    ResponseTooLarge {
+        /// The response size cap that was exceeded
+        max_response_bytes: usize,
+    },
+}
 
 /// Struct for interacting with the ZK Query APIs
 #[derive(Debug, Clone)]
@@ -19,25 +108,92 @@ pub struct ZkQueryClient {
     pub client: Client,
     /// Access token for authentication, obtained using the API key
     pub access_token: String,
+    /// Maximum time to wait for any single request
+    pub request_timeout: Duration,
+    /// How many times [`ZkQueryClient::run_zk_query`] resubmits a query that ends with the
+    /// `Failed` status, backing off between attempts. Canceled queries are never resubmitted.
+    pub failed_query_retries: u32,
+    /// How long to wait between polls of the status of a running query
+    pub poll_strategy: PollStrategy,
+    /// Maximum size in bytes of any single response body
+    pub max_response_bytes: usize,
 }
 
 impl ZkQueryClient {
+    /// Create a new client using the [`DEFAULT_REQUEST_TIMEOUT`] and
+    /// [`DEFAULT_MAX_RESPONSE_BYTES`].
+    pub fn new(base_url: Url, access_token: String) -> Self {
+        Self {
+            base_url,
+            client: Client::new(),
+            access_token,
+            request_timeout: DEFAULT_REQUEST_TIMEOUT,
+            failed_query_retries: 0,
+            poll_strategy: PollStrategy::default(),
+            max_response_bytes: DEFAULT_MAX_RESPONSE_BYTES,
+        }
+    }
+
+    /// Maps a request error, reporting timeouts as [`ZkQueryClientError::RequestTimeout`].
+    fn request_error(&self, error: reqwest::Error) -> Box<dyn core::error::Error> {
+        if error.is_timeout() {
+            Box::new(ZkQueryClientError::RequestTimeout {
+                timeout: self.request_timeout,
+            })
+        } else {
+            Box::new(error)
+        }
+    }
+
+    /// Sends an authenticated request and reads its response body, failing with
+    /// [`ZkQueryClientError::RequestTimeout`] if it takes longer than `request_timeout`.
+    ///
+    /// The body is read in chunks and fails with [`ZkQueryClientError::ResponseTooLarge`] once it
+    /// exceeds `max_response_bytes`, so an oversized response is never buffered in full.
+    async fn send(&self, request: RequestBuilder) -> Result<Vec<u8>, Box<dyn core::error::Error>> {
+        let mut response = request
+            .bearer_auth(&self.access_token)
+            .timeout(self.request_timeout)
+            .send()
+            .await
+            .map_err(|error| self.request_error(error))?
+            .error_for_status()?;
+        let too_large = || ZkQueryClientError::ResponseTooLarge {
+            max_response_bytes: self.max_response_bytes,
+        };
+        if response
+            .content_length()
+            .is_some_and(|len| len > self.max_response_bytes as u64)
+        {
+            return Err(Box::new(too_large()));
+        }
+        let mut body = Vec::new();
+        while let Some(chunk) = response
+            .chunk()
+            .await
+            .map_err(|error| self.request_error(error))?
+        {
+            if body.len() + chunk.len() > self.max_response_bytes {
+                return Err(Box::new(too_large()));
+            }
+            body.extend_from_slice(&chunk);
+        }
+        Ok(body)
+    }
+
     /// Submits a request for a zk query
     async fn submit_zk_query(
         &self,
         request: QuerySubmitRequest,
     ) -> Result<QuerySubmitResponse, Box<dyn core::error::Error>> {
-        let response = self
-            .client
-            .post(self.base_url.join("/v1/zkquery")?)
-            .bearer_auth(&self.access_token)
-            .json(&request)
-            .send()
-            .await?
-            .error_for_status()?;
-        Ok(response
-            .json::<QuerySubmitResponse>()
-            .await
+        let body = self
+            .send(
+                self.client
+                    .post(self.base_url.join("/v1/zkquery")?)
+                    .json(&request),
+            )
+            .await?;
+        Ok(serde_json::from_slice::<QuerySubmitResponse>(&body)
             .map_err(|e| format!("Failed to parse query submit response: {}", &e))?)
     }
 
@@ -46,19 +202,15 @@ impl ZkQueryClient {
         &self,
         query_id: String,
     ) -> Result<QueryStatusResponse, Box<dyn core::error::Error>> {
-        let response = self
-            .client
-            .get(
-                self.base_url
-                    .join(&format!("/v1/zkquery/{}/status", &query_id))?,
+        let body = self
+            .send(
+                self.client.get(
+                    self.base_url
+                        .join(&format!("/v1/zkquery/{}/status", &query_id))?,
+                ),
             )
-            .bearer_auth(&self.access_token)
-            .send()
-            .await?
-            .error_for_status()?;
-        Ok(response
-            .json::<QueryStatusResponse>()
-            .await
+            .await?;
+        Ok(serde_json::from_slice::<QueryStatusResponse>(&body)
             .map_err(|e| format!("Failed to parse query status response: {}", &e))?)
     }
 
@@ -67,36 +219,57 @@ impl ZkQueryClient {
         &self,
         query_id: String,
     ) -> Result<QueryResultsResponse, Box<dyn core::error::Error>> {
-        let response = self
-            .client
-            .get(
-                self.base_url
-                    .join(&format!("/v1/zkquery/{}/results", &query_id))?,
+        let body = self
+            .send(
+                self.client.get(
+                    self.base_url
+                        .join(&format!("/v1/zkquery/{}/results", &query_id))?,
+                ),
             )
-            .bearer_auth(&self.access_token)
-            .send()
-            .await?
-            .error_for_status()?;
-        Ok(response
-            .json::<QueryResultsResponse>()
-            .await
+            .await?;
+        Ok(serde_json::from_slice::<QueryResultsResponse>(&body)
             .map_err(|e| format!("Failed to parse query results response: {}", &e))?)
     }
 
+    /// Lists zk queries, most recent first, along with their current status.
+    ///
+    /// Useful for reconnecting to in-flight queries after a restart.
+    pub async fn list_zk_queries(
+        &self,
+        limit: u32,
+        offset: u32,
+    ) -> Result<Vec<QueryStatusResponse>, Box<dyn core::error::Error>> {
+        let mut url = self.base_url.join("/v1/zkquery")?;
+        url.query_pairs_mut()
+            .append_pair("limit", &limit.to_string())
+            .append_pair("offset", &offset.to_string());
+        let body = self.send(self.client.get(url)).await?;
+        Ok(serde_json::from_slice::<QueryListResponse>(&body)
+            .map_err(|e| format!("Failed to parse query list response: {}", &e))?
+            .queries)
+    }
+
+    /// Looks up the current status of a single zk query.
+    pub async fn get_zk_query(
+        &self,
+        query_id: String,
+    ) -> Result<QueryStatusResponse, Box<dyn core::error::Error>> {
+        self.poll_zk_query_status(query_id).await
+    }
+
     /// Requests a proof plan from the ZK Query API.
     pub async fn get_zk_query_plan(
         &self,
         request: QueryPlanRequest,
     ) -> Result<QueryPlanResponse, Box<dyn core::error::Error>> {
-        let response = self
-            .client
-            .post(self.base_url.join("/v1/zkquery/build-plan")?)
-            .bearer_auth(&self.access_token)
-            .json(&request)
-            .send()
-            .await?
-            .error_for_status()?;
-        let serialized_response = response.text().await?;
+        let body = self
+            .send(
+                self.client
+                    .post(self.base_url.join("/v1/zkquery/build-plan")?)
+                    .json(&request),
+            )
+            .await?;
+        let serialized_response = String::from_utf8_lossy(&body);
         Ok(
             serde_json::from_str::<QueryPlanResponse>(&serialized_response).map_err(|_e| {
                 format!(
@@ -107,44 +280,73 @@ impl ZkQueryClient {
         )
     }
 
-    /// Orchestrates retry logic on polling the status of a zk query.
-    #[expect(clippy::type_complexity)]
-    fn wait_for_completed_status<'a>(
-        &'a self,
-        query_id: &'a String,
-        milliseconds_to_retry: u64,
-    ) -> Pin<Box<dyn Future<Output = Result<ZkQueryStatus, Box<dyn core::error::Error>>> + 'a>>
-    {
-        Box::pin(async move {
-            let status = self.poll_zk_query_status(query_id.clone()).await?.status;
-            match status {
-                ZkQueryStatus::Done | ZkQueryStatus::Canceled | ZkQueryStatus::Failed => Ok(status),
-                _ => {
-                    tokio::time::sleep(tokio::time::Duration::from_millis(milliseconds_to_retry))
-                        .await;
-                    let new_milliseconds_to_retry =
-                        if milliseconds_to_retry <= MAX_MILLISECONDS_TO_RETRY / 2 {
-                            2 * milliseconds_to_retry
-                        } else {
-                            MAX_MILLISECONDS_TO_RETRY
-                        };
-                    self.wait_for_completed_status(query_id, new_milliseconds_to_retry)
-                        .await
-                }
+    /// Polls the status of a zk query until it is done, canceled or failed, waiting between
+    /// polls according to `poll_strategy`.
+    async fn wait_for_completed_status(
+        &self,
+        query_id: &str,
+    ) -> Result<ZkQueryStatus, Box<dyn core::error::Error>> {
+        let mut poll = 0;
+        loop {
+            let status = self
+                .poll_zk_query_status(query_id.to_string())
+                .await?
+                .status;
+            if let ZkQueryStatus::Done | ZkQueryStatus::Canceled | ZkQueryStatus::Failed = status {
+                return Ok(status);
             }
-        })
+            tokio::time::sleep(self.poll_strategy.delay(poll)).await;
+            poll = poll.saturating_add(1);
+        }
     }
 
     /// Orchestrates the API requests that are need to run a zk query
+    ///
+    /// A query that fails is resubmitted up to `failed_query_retries` times.
     pub async fn run_zk_query(
         &self,
         request: QuerySubmitRequest,
     ) -> Result<QueryResultsResponse, Box<dyn core::error::Error>> {
+        let mut milliseconds_to_resubmit = INITIAL_MILLISECONDS_TO_RESUBMIT;
+        for _ in 0..self.failed_query_retries {
+            let query_id = self
+                .submit_zk_query(request.clone())
+                .await?
+                .query_id
+                .to_string();
+            let status = self.wait_for_completed_status(&query_id).await?;
+            if status != ZkQueryStatus::Failed {
+                return self.get_results_for_status(query_id, status).await;
+            }
+            log::warn!(
+                "query {query_id} failed, resubmitting in {milliseconds_to_resubmit} milliseconds"
+            );
+            tokio::time::sleep(Duration::from_millis(milliseconds_to_resubmit)).await;
+            milliseconds_to_resubmit =
+                (2 * milliseconds_to_resubmit).min(MAX_MILLISECONDS_TO_RETRY);
+        }
         let query_submit_response = self.submit_zk_query(request).await?;
-        let query_id = query_submit_response.query_id.to_string();
-        let status = self
-            .wait_for_completed_status(&query_id, INITIAL_MILLISECONDS_TO_RETRY)
-            .await?;
+        self.resume_zk_query(query_submit_response.query_id.to_string())
+            .await
+    }
+
+    /// Waits for an already submitted zk query to complete and fetches its results.
+    ///
+    /// Use this to pick up a query submitted before a restart without proving it again.
+    pub async fn resume_zk_query(
+        &self,
+        query_id: String,
+    ) -> Result<QueryResultsResponse, Box<dyn core::error::Error>> {
+        let status = self.wait_for_completed_status(&query_id).await?;
+        self.get_results_for_status(query_id, status).await
+    }
+
+    /// Fetches the results of a zk query that reached `status`, failing unless it is done.
+    async fn get_results_for_status(
+        &self,
+        query_id: String,
+        status: ZkQueryStatus,
+    ) -> Result<QueryResultsResponse, Box<dyn core::error::Error>> {
         if status == ZkQueryStatus::Done {
             Ok(self.get_zk_query_results(query_id).await?)
         } else {
@@ -156,8 +358,354 @@ impl ZkQueryClient {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{base::zk_query_models::SxtNetwork, native::auth::get_access_token};
+    use crate::{
+        base::zk_query_models::{serialize_query_params, SxtNetwork},
+        native::{
+            auth::get_access_token,
+            mock_server::{mock_server, request_lines},
+        },
+    };
     use dotenv::dotenv;
+    use proof_of_sql::base::database::LiteralValue;
+    use std::net::TcpListener;
+
+    fn mock_client(base_url: Url) -> ZkQueryClient {
+        ZkQueryClient::new(base_url, "token".to_string())
+    }
+
+    fn status_json(query_id: &str, status: &str) -> String {
+        format!(
+            r#"{{"queryId":"{query_id}","created":"2026-03-19T04:19:10.438548Z","commitmentScheme":"HYPER_KZG","status":"{status}"}}"#
+        )
+    }
+
+    const RUNNING_QUERY_ID: &str = "060e98dd-63a3-40f3-9ca3-cf2451b9bd36";
+    const DONE_QUERY_ID: &str = "5b0f8a51-0a3c-4c8e-9d6e-2f3a1c7b9e10";
+
+    #[tokio::test]
+    async fn we_can_list_zk_queries() {
+        let (base_url, requests) = mock_server(vec![(
+            "/v1/zkquery?limit=2&offset=0",
+            format!(
+                r#"{{"queries":[{},{}]}}"#,
+                status_json(RUNNING_QUERY_ID, "running"),
+                status_json(DONE_QUERY_ID, "done")
+            ),
+        )]);
+
+        let queries = mock_client(base_url).list_zk_queries(2, 0).await.unwrap();
+
+        assert_eq!(queries.len(), 2);
+        assert_eq!(queries[0].query_id.to_string(), RUNNING_QUERY_ID);
+        assert_eq!(queries[0].status, ZkQueryStatus::Running);
+        assert_eq!(queries[1].query_id.to_string(), DONE_QUERY_ID);
+        assert_eq!(queries[1].status, ZkQueryStatus::Done);
+        assert_eq!(
+            request_lines(&requests),
+            vec!["GET /v1/zkquery?limit=2&offset=0 HTTP/1.1".to_string()]
+        );
+    }
+
+    #[tokio::test]
+    async fn we_can_get_a_single_zk_query() {
+        let (base_url, _) = mock_server(vec![(
+            "/v1/zkquery/060e98dd-63a3-40f3-9ca3-cf2451b9bd36/status",
+            status_json(RUNNING_QUERY_ID, "queued"),
+        )]);
+
+        let query = mock_client(base_url)
+            .get_zk_query(RUNNING_QUERY_ID.to_string())
+            .await
+            .unwrap();
+
+        assert_eq!(query.query_id.to_string(), RUNNING_QUERY_ID);
+        assert_eq!(query.status, ZkQueryStatus::Queued);
+    }
+
+    #[tokio::test]
+    async fn we_can_resume_a_zk_query_without_resubmitting() {
+        let (base_url, requests) = mock_server(vec![
+            (
+                "/v1/zkquery/060e98dd-63a3-40f3-9ca3-cf2451b9bd36/status",
+                status_json(RUNNING_QUERY_ID, "done"),
+            ),
+            (
+                "/v1/zkquery/060e98dd-63a3-40f3-9ca3-cf2451b9bd36/results",
+                include_str!("../../../../test_assets/valid_gateway_response.json").to_string(),
+            ),
+        ]);
+
+        let results = mock_client(base_url)
+            .resume_zk_query(RUNNING_QUERY_ID.to_string())
+            .await
+            .unwrap();
+
+        assert_eq!(results.query_id.to_string(), RUNNING_QUERY_ID);
+        assert!(results.success);
+        assert_eq!(
+            request_lines(&requests),
+            vec![
+                "GET /v1/zkquery/060e98dd-63a3-40f3-9ca3-cf2451b9bd36/status HTTP/1.1".to_string(),
+                "GET /v1/zkquery/060e98dd-63a3-40f3-9ca3-cf2451b9bd36/results HTTP/1.1".to_string(),
+            ]
+        );
+    }
+
+    fn submit_request() -> QuerySubmitRequest {
+        QuerySubmitRequest {
+            sql_text: "SELECT * FROM ETHEREUM.BLOCKS".to_string(),
+            source_network: SxtNetwork::Mainnet,
+            timeout: None,
+            commitment_scheme: crate::base::prover::CommitmentScheme::HyperKzg,
+            block_hash: None,
+            params: None,
+        }
+    }
+
+    /// Routes for a query that fails when first submitted and succeeds when resubmitted.
+    fn failing_then_done_routes() -> Vec<(&'static str, String)> {
+        vec![
+            (
+                "/v1/zkquery",
+                format!(
+                    r#"{{"queryId":"{DONE_QUERY_ID}","created":"2026-03-19T04:19:10.438548Z","commitmentScheme":"HYPER_KZG"}}"#
+                ),
+            ),
+            (
+                "/v1/zkquery",
+                format!(
+                    r#"{{"queryId":"{RUNNING_QUERY_ID}","created":"2026-03-19T04:19:10.438548Z","commitmentScheme":"HYPER_KZG"}}"#
+                ),
+            ),
+            (
+                "/v1/zkquery/5b0f8a51-0a3c-4c8e-9d6e-2f3a1c7b9e10/status",
+                status_json(DONE_QUERY_ID, "failed"),
+            ),
+            (
+                "/v1/zkquery/060e98dd-63a3-40f3-9ca3-cf2451b9bd36/status",
+                status_json(RUNNING_QUERY_ID, "done"),
+            ),
+            (
+                "/v1/zkquery/060e98dd-63a3-40f3-9ca3-cf2451b9bd36/results",
+                include_str!("../../../../test_assets/valid_gateway_response.json").to_string(),
+            ),
+        ]
+    }
+
+    #[tokio::test]
+    async fn we_can_resubmit_a_failed_zk_query() {
+        let (base_url, requests) = mock_server(failing_then_done_routes());
+        let client = ZkQueryClient {
+            failed_query_retries: 1,
+            ..mock_client(base_url)
+        };
+
+        let results = client.run_zk_query(submit_request()).await.unwrap();
+
+        assert_eq!(results.query_id.to_string(), RUNNING_QUERY_ID);
+        assert_eq!(
+            request_lines(&requests),
+            vec![
+                "POST /v1/zkquery HTTP/1.1".to_string(),
+                "GET /v1/zkquery/5b0f8a51-0a3c-4c8e-9d6e-2f3a1c7b9e10/status HTTP/1.1".to_string(),
+                "POST /v1/zkquery HTTP/1.1".to_string(),
+                "GET /v1/zkquery/060e98dd-63a3-40f3-9ca3-cf2451b9bd36/status HTTP/1.1".to_string(),
+                "GET /v1/zkquery/060e98dd-63a3-40f3-9ca3-cf2451b9bd36/results HTTP/1.1".to_string(),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn we_do_not_resubmit_failed_zk_queries_by_default() {
+        let (base_url, requests) = mock_server(failing_then_done_routes());
+
+        let error = mock_client(base_url)
+            .run_zk_query(submit_request())
+            .await
+            .unwrap_err();
+
+        assert_eq!(error.to_string(), "Final status for query: Failed");
+        assert_eq!(
+            request_lines(&requests),
+            vec![
+                "POST /v1/zkquery HTTP/1.1".to_string(),
+                "GET /v1/zkquery/5b0f8a51-0a3c-4c8e-9d6e-2f3a1c7b9e10/status HTTP/1.1".to_string(),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn we_can_submit_a_parameterized_zk_query() {
+        let (base_url, requests) = mock_server(vec![(
+            "/v1/zkquery",
+            format!(
+                r#"{{"queryId":"{RUNNING_QUERY_ID}","created":"2026-03-19T04:19:10.438548Z","commitmentScheme":"HYPER_KZG"}}"#
+            ),
+        )]);
+        let params =
+            serialize_query_params(&[LiteralValue::BigInt(22419300), LiteralValue::Boolean(true)])
+                .unwrap();
+
+        let response = mock_client(base_url)
+            .submit_zk_query(QuerySubmitRequest {
+                sql_text: "SELECT * FROM ETHEREUM.BLOCKS WHERE BLOCK_NUMBER = $1 AND $2"
+                    .to_string(),
+                source_network: SxtNetwork::Mainnet,
+                timeout: None,
+                commitment_scheme: crate::base::prover::CommitmentScheme::HyperKzg,
+                block_hash: None,
+                params: Some(params.clone()),
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(response.query_id.to_string(), RUNNING_QUERY_ID);
+        let requests = requests.lock().unwrap();
+        assert_eq!(requests[0].0, "POST /v1/zkquery HTTP/1.1");
+        let body: serde_json::Value = serde_json::from_str(&requests[0].1).unwrap();
+        assert!(params.starts_with("0x"));
+        assert_eq!(body["params"], params);
+    }
+
+    #[tokio::test]
+    async fn we_request_a_non_evm_plan_for_dynamic_dory() {
+        let (base_url, requests) = mock_server(vec![(
+            "/v1/zkquery/build-plan",
+            r#"{"plan":"0x00"}"#.to_string(),
+        )]);
+
+        mock_client(base_url)
+            .get_zk_query_plan(QueryPlanRequest::new(
+                "SELECT * FROM ETHEREUM.BLOCKS".to_string(),
+                SxtNetwork::Mainnet,
+                Some(crate::base::prover::CommitmentScheme::DynamicDory),
+            ))
+            .await
+            .unwrap();
+
+        let requests = requests.lock().unwrap();
+        assert_eq!(requests[0].0, "POST /v1/zkquery/build-plan HTTP/1.1");
+        let body: serde_json::Value = serde_json::from_str(&requests[0].1).unwrap();
+        assert_eq!(body["commitmentScheme"], "DYNAMIC_DORY");
+        assert_eq!(body["evmCompatible"], false);
+    }
+
+    #[test]
+    fn we_can_compute_poll_delays() {
+        let fixed = PollStrategy::Fixed(Duration::from_millis(5));
+        assert!((0..100).all(|poll| fixed.delay(poll) == Duration::from_millis(5)));
+
+        let exponential = PollStrategy::Exponential {
+            initial: Duration::from_millis(10),
+            max: Duration::from_millis(50),
+        };
+        assert_eq!(
+            (0..5)
+                .map(|poll| exponential.delay(poll))
+                .collect::<Vec<_>>(),
+            [10, 20, 40, 50, 50].map(Duration::from_millis)
+        );
+        assert_eq!(exponential.delay(u32::MAX), Duration::from_millis(50));
+        assert_eq!(PollStrategy::default().delay(0), Duration::from_millis(10));
+
+        assert_eq!(PollStrategy::Adaptive.delay(0), Duration::from_millis(50));
+        assert_eq!(PollStrategy::Adaptive.delay(19), Duration::from_millis(50));
+        assert_eq!(PollStrategy::Adaptive.delay(20), Duration::from_millis(100));
+        assert_eq!(PollStrategy::Adaptive.delay(21), Duration::from_millis(200));
+    }
+
+    #[tokio::test]
+    async fn we_poll_at_a_fixed_interval() {
+        let status_route = "/v1/zkquery/060e98dd-63a3-40f3-9ca3-cf2451b9bd36/status";
+        let mut routes = vec![(status_route, status_json(RUNNING_QUERY_ID, "running")); 4];
+        routes.push((status_route, status_json(RUNNING_QUERY_ID, "done")));
+        routes.push((
+            "/v1/zkquery/060e98dd-63a3-40f3-9ca3-cf2451b9bd36/results",
+            include_str!("../../../../test_assets/valid_gateway_response.json").to_string(),
+        ));
+        let (base_url, requests) = mock_server(routes);
+        let client = ZkQueryClient {
+            poll_strategy: PollStrategy::Fixed(Duration::from_millis(100)),
+            ..mock_client(base_url)
+        };
+
+        let start = std::time::Instant::now();
+        client
+            .resume_zk_query(RUNNING_QUERY_ID.to_string())
+            .await
+            .unwrap();
+        let elapsed = start.elapsed();
+
+        // Four running statuses, each followed by one fixed wait. Only the lower bound is checked,
+        // since a loaded machine may take arbitrarily longer; it still rules out the default
+        // strategy, which would have waited 150 milliseconds. The waits themselves are checked by
+        // `we_can_compute_poll_delays`.
+        assert_eq!(request_lines(&requests).len(), 6);
+        assert!(elapsed >= Duration::from_millis(400));
+    }
+
+    #[tokio::test]
+    async fn we_reject_a_response_over_the_size_cap() {
+        let fixture = include_str!("../../../../test_assets/valid_gateway_response.json");
+        let (base_url, _) = mock_server(vec![(
+            "/v1/zkquery/060e98dd-63a3-40f3-9ca3-cf2451b9bd36/results",
+            fixture.to_string(),
+        )]);
+        let client = ZkQueryClient {
+            max_response_bytes: fixture.len() - 1,
+            ..mock_client(base_url.clone())
+        };
+
+        let error = client
+            .get_zk_query_results(RUNNING_QUERY_ID.to_string())
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<ZkQueryClientError>(),
+            Some(ZkQueryClientError::ResponseTooLarge { max_response_bytes })
+                if *max_response_bytes == fixture.len() - 1
+        ));
+
+        // The cap is per client, so the default client still reads the same response
+        mock_client(base_url)
+            .get_zk_query_results(RUNNING_QUERY_ID.to_string())
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn we_time_out_requests_to_a_slow_endpoint() {
+        // Accept connections but never answer them
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base_url = Url::parse(&format!("http://{}", listener.local_addr().unwrap())).unwrap();
+        std::thread::spawn(move || {
+            let _connections: Vec<_> = listener.incoming().collect();
+        });
+        let client = ZkQueryClient {
+            request_timeout: Duration::from_millis(100),
+            ..mock_client(base_url)
+        };
+
+        let error = client
+            .get_zk_query(RUNNING_QUERY_ID.to_string())
+            .await
+            .unwrap_err();
+
+        assert!(matches!(
+            error.downcast_ref::<ZkQueryClientError>(),
+            Some(ZkQueryClientError::RequestTimeout { timeout })
+                if *timeout == Duration::from_millis(100)
+        ));
+    }
+
+    #[tokio::test]
+    async fn we_cannot_get_an_unknown_zk_query() {
+        let (base_url, _) = mock_server(vec![]);
+        assert!(mock_client(base_url)
+            .get_zk_query(RUNNING_QUERY_ID.to_string())
+            .await
+            .is_err());
+    }
 
     #[tokio::test]
     #[ignore]
@@ -175,11 +723,7 @@ mod tests {
             .await
             .expect("Failed to get access token");
 
-        let client = ZkQueryClient {
-            base_url: zk_query_root_url.clone(),
-            client: Client::new(),
-            access_token,
-        };
+        let client = ZkQueryClient::new(zk_query_root_url.clone(), access_token);
 
         let queries = vec![
             "select block_number from ethereum.blocks limit 5",
@@ -187,11 +731,7 @@ mod tests {
         ];
 
         for query in queries {
-            let request = QueryPlanRequest {
-                sql_text: query.to_string(),
-                source_network: SxtNetwork::Mainnet,
-                evm_compatible: true,
-            };
+            let request = QueryPlanRequest::new(query.to_string(), SxtNetwork::Mainnet, None);
 
             let result = client.get_zk_query_plan(request).await;
             assert!(result.is_ok(), "Query '{}' should succeed", query);
@@ -214,19 +754,16 @@ mod tests {
             .await
             .expect("Failed to get access token");
 
-        let client = ZkQueryClient {
-            base_url: zk_query_root_url,
-            client: Client::new(),
-            access_token,
-        };
+        let client = ZkQueryClient::new(zk_query_root_url, access_token);
 
         let query = "SELECT BLOCK_NUMBER FROM ETHEREUM.BLOCKS WHERE BLOCK_NUMBER=22419300";
         let request = QuerySubmitRequest {
             sql_text: query.to_string(),
             source_network: SxtNetwork::Mainnet,
             timeout: None,
-            commitment_scheme: Some(crate::base::prover::CommitmentScheme::HyperKzg),
+            commitment_scheme: crate::base::prover::CommitmentScheme::HyperKzg,
             block_hash: None,
+            params: None,
         };
 
         let result = client.run_zk_query(request).await;
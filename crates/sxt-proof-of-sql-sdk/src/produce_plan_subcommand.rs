@@ -70,6 +70,7 @@ pub async fn produce_plan_command(
         args.auth_root_url,
         &args.sxt_api_key,
         &args.query,
+        &[],
         args.network,
     )
     .await?;
@@ -1,24 +1,43 @@
 use crate::{
     base::{zk_query_models::SxtNetwork, CommitmentScheme},
-    native::SxTClient,
+    native::{produce_plan, CheckedResult, DynOwnedTable, SxTClient},
 };
 use arrow_csv::WriterBuilder;
-use clap::Args;
+use clap::{Args, ValueEnum};
 use datafusion::arrow::{
-    array::{BinaryArray, FixedSizeBinaryArray, LargeBinaryArray, StringArray},
+    array::{ArrayRef, BinaryArray, FixedSizeBinaryArray, LargeBinaryArray, StringArray},
+    compute::cast,
     datatypes::DataType,
+    error::ArrowError,
     record_batch::RecordBatch,
     util::pretty::pretty_format_batches,
 };
-use std::{path::PathBuf, sync::Arc};
+use parquet::arrow::ArrowWriter;
+use proof_of_sql::base::{
+    database::{OwnedColumn, OwnedTable},
+    scalar::{Scalar, ScalarExt},
+};
+use serde_json::Value;
+use std::{io::Write, path::PathBuf, sync::Arc};
 use subxt::utils::H256;
 use url::Url;
 
+/// How a verified result is printed.
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OutputFormat {
+    /// A human-readable table
+    #[default]
+    Table,
+    /// One JSON object per row, for piping into other tools
+    Jsonl,
+}
+
 #[derive(Args, Debug, Clone, PartialEq, Eq)]
 pub struct QueryAndVerifySdkArgs {
     /// SXT Network
     ///
-    /// The SXT network to use.
+    /// The SXT network whose attestations the result is verified against. See `source_network`
+    /// for where the queried data lives.
     /// Can be set via SXT_NETWORK environment variable.
     #[arg(
         long,
@@ -67,6 +86,8 @@ pub struct QueryAndVerifySdkArgs {
     pub block_hash: Option<H256>,
 
     /// Commitment scheme to use for the query
+    ///
+    /// Defaults to `HyperKzg`, as do the other subcommands and the examples.
     #[arg(
         long,
         value_enum,
@@ -89,9 +110,49 @@ pub struct QueryAndVerifySdkArgs {
     #[arg(long)]
     pub csv_file_path: Option<PathBuf>,
 
+    /// The results will be put in a Parquet file at the output path, keeping the native column
+    /// types. If `None`, no Parquet file will be saved
+    #[arg(long)]
+    pub parquet_file_path: Option<PathBuf>,
+
     /// The source of the data
+    ///
+    /// The network the queried tables live on, which is sent with the query. Unlike `network`,
+    /// this does not change what the result is verified against.
     #[arg(long, value_enum, env, default_value_t=SxtNetwork::Mainnet)]
     pub source_network: SxtNetwork,
+
+    /// Print the proof plan for the query before running it
+    #[arg(long)]
+    pub explain: bool,
+
+    /// Print the proof plan for the query and stop without running it
+    #[arg(long)]
+    pub explain_only: bool,
+
+    /// Only print (and write to csv) the first N rows of the result
+    ///
+    /// The full result is still verified; this only truncates the output.
+    #[arg(long, value_name = "N")]
+    pub limit_rows: Option<usize>,
+
+    /// Resubmit the query up to N times if the ZK Query API reports it as failed
+    #[arg(long, value_name = "N", default_value_t = 0)]
+    pub retry_on_failed: u32,
+
+    /// How to print the verified result
+    ///
+    /// `jsonl` prints one JSON object per row. As in the JavaScript bindings, integers wider than
+    /// 32 bits, decimals, scalars and timestamps are strings, and binary values are hex strings.
+    #[arg(long, value_enum, default_value_t = OutputFormat::Table)]
+    pub output_format: OutputFormat,
+
+    /// Print the verified result as bare CSV instead of a table
+    ///
+    /// Nothing else is printed on success, so the output can be consumed by scripts, and
+    /// `--explain` is ignored. Failures are reported through the exit code, see `CliExitCode`.
+    #[arg(long)]
+    pub quiet: bool,
 }
 
 impl From<&QueryAndVerifySdkArgs> for (SxTClient, CommitmentScheme) {
@@ -103,12 +164,53 @@ impl From<&QueryAndVerifySdkArgs> for (SxTClient, CommitmentScheme) {
                 args.auth_root_url.clone(),
                 args.sxt_api_key.clone(),
                 args.verifier_setup.clone(),
-            ),
+            )
+            .with_failed_query_retries(args.retry_on_failed)
+            .with_source_network(args.source_network),
             args.commitment_scheme,
         )
     }
 }
 
+/// Converts an array to a type the CSV writer renders well.
+///
+/// Binary values are hex encoded from their own bytes, so fixed size columns of different widths
+/// keep their widths, and null values stay null. Dictionary-encoded arrays are decoded to their
+/// values and `LargeUtf8` is narrowed to `Utf8`.
+fn cast_array_to_csv_friendly_array(arr: &ArrayRef) -> ArrayRef {
+    match arr.data_type() {
+        DataType::LargeBinary => Arc::new(StringArray::from(
+            arr.as_any()
+                .downcast_ref::<LargeBinaryArray>()
+                .expect("Array should be LargeBinary")
+                .into_iter()
+                .map(|bin| bin.map(hex::encode))
+                .collect::<Vec<_>>(),
+        )),
+        DataType::FixedSizeBinary(_) => Arc::new(StringArray::from(
+            arr.as_any()
+                .downcast_ref::<FixedSizeBinaryArray>()
+                .expect("Array should be FixedSizeBinary")
+                .into_iter()
+                .map(|bin| bin.map(hex::encode))
+                .collect::<Vec<_>>(),
+        )),
+        DataType::Binary => Arc::new(StringArray::from(
+            arr.as_any()
+                .downcast_ref::<BinaryArray>()
+                .expect("Array should be BinaryArray")
+                .into_iter()
+                .map(|bin| bin.map(hex::encode))
+                .collect::<Vec<_>>(),
+        )),
+        DataType::Dictionary(_, value_type) => cast_array_to_csv_friendly_array(
+            &cast(arr, value_type).expect("Dictionary should cast to its value type"),
+        ),
+        DataType::LargeUtf8 => cast(arr, &DataType::Utf8).expect("LargeUtf8 should cast to Utf8"),
+        _ => arr.clone(),
+    }
+}
+
 fn cast_record_batch_to_csv_friendly_record_batch(record_batch: RecordBatch) -> RecordBatch {
     RecordBatch::try_from_iter(
         record_batch
@@ -116,76 +218,302 @@ fn cast_record_batch_to_csv_friendly_record_batch(record_batch: RecordBatch) ->
             .fields()
             .iter()
             .zip(record_batch.columns().iter())
-            .map(|(field, arr)| {
-                (
-                    field.name(),
-                    match field.data_type().clone() {
-                        DataType::LargeBinary => Arc::new(StringArray::from(
-                            arr.as_any()
-                                .downcast_ref::<LargeBinaryArray>()
-                                .expect("Array should be LargeBinary")
-                                .into_iter()
-                                .map(|bin| hex::encode(bin.unwrap()))
-                                .collect::<Vec<_>>(),
-                        )),
-                        DataType::FixedSizeBinary(_) => Arc::new(StringArray::from(
-                            arr.as_any()
-                                .downcast_ref::<FixedSizeBinaryArray>()
-                                .expect("Array should be FixedSizeBinary")
-                                .into_iter()
-                                .map(|bin| hex::encode(bin.unwrap()))
-                                .collect::<Vec<_>>(),
-                        )),
-                        DataType::Binary => Arc::new(StringArray::from(
-                            arr.as_any()
-                                .downcast_ref::<BinaryArray>()
-                                .expect("Array should be BinaryArray")
-                                .into_iter()
-                                .map(|bin| hex::encode(bin.unwrap()))
-                                .collect::<Vec<_>>(),
-                        )),
-                        _ => arr.clone(),
-                    },
-                )
-            }),
+            .map(|(field, arr)| (field.name(), cast_array_to_csv_friendly_array(arr))),
     )
     .unwrap()
 }
 
+/// Formats a scalar as a signed integer string without loss of precision.
+fn scalar_to_exact_string<S: Scalar>(scalar: &S) -> String {
+    if *scalar > S::MAX_SIGNED {
+        format!("-{}", (-*scalar).into_u256_wrapping())
+    } else {
+        scalar.into_u256_wrapping().to_string()
+    }
+}
+
+/// Formats an unscaled decimal value with `scale` fractional digits without loss of precision.
+fn decimal_to_exact_string<S: Scalar>(value: &S, scale: i8) -> String {
+    let unscaled = scalar_to_exact_string(value);
+    let (sign, digits) = match unscaled.strip_prefix('-') {
+        Some(digits) => ("-", digits),
+        None => ("", unscaled.as_str()),
+    };
+    let num_fractional_digits = usize::from(scale.unsigned_abs());
+    if scale <= 0 {
+        return if digits == "0" {
+            digits.to_string()
+        } else {
+            format!("{sign}{digits}{}", "0".repeat(num_fractional_digits))
+        };
+    }
+    let digits = format!("{digits:0>width$}", width = num_fractional_digits + 1);
+    let (integer, fraction) = digits.split_at(digits.len() - num_fractional_digits);
+    format!("{sign}{integer}.{fraction}")
+}
+
+/// Converts a verified table into a record batch that can be written as CSV.
+///
+/// Decimal75 and Scalar columns are rendered as exact decimal strings rather than going through
+/// Arrow, so no digits are lost.
+fn owned_table_to_csv_friendly_record_batch<S: Scalar>(
+    table: OwnedTable<S>,
+) -> Result<RecordBatch, ArrowError> {
+    let record_batch =
+        RecordBatch::try_from_iter(table.into_inner().into_iter().map(|(ident, column)| {
+            let array: ArrayRef = match column {
+                OwnedColumn::Decimal75(_, scale, values) => Arc::new(StringArray::from(
+                    values
+                        .iter()
+                        .map(|value| decimal_to_exact_string(value, scale))
+                        .collect::<Vec<_>>(),
+                )),
+                OwnedColumn::Scalar(values) => Arc::new(StringArray::from(
+                    values
+                        .iter()
+                        .map(scalar_to_exact_string)
+                        .collect::<Vec<_>>(),
+                )),
+                column => column.into(),
+            };
+            (ident.value, array)
+        }))?;
+    Ok(cast_record_batch_to_csv_friendly_record_batch(record_batch))
+}
+
+/// Writes a verified table as CSV, keeping only the first `limit_rows` rows if set.
+fn write_csv(
+    table: DynOwnedTable,
+    limit_rows: Option<usize>,
+    writer: impl Write,
+) -> Result<(), ArrowError> {
+    let record_batch = match table {
+        DynOwnedTable::Dory(table) => owned_table_to_csv_friendly_record_batch(table),
+        #[cfg(feature = "hyperkzg")]
+        DynOwnedTable::BN(table) => owned_table_to_csv_friendly_record_batch(table),
+    }?;
+    WriterBuilder::new()
+        .build(writer)
+        .write(&first_rows(&record_batch, limit_rows))
+}
+
+/// Writes a verified table as Parquet, keeping only the first `limit_rows` rows if set.
+///
+/// Unlike [`write_csv`], the columns keep their Arrow types, so binary and decimal values are
+/// written as such.
+fn write_parquet(
+    table: DynOwnedTable,
+    limit_rows: Option<usize>,
+    writer: impl Write + Send,
+) -> Result<(), Box<dyn core::error::Error>> {
+    let record_batch = first_rows(&RecordBatch::try_from(table)?, limit_rows);
+    let mut writer = ArrowWriter::try_new(writer, record_batch.schema(), None)?;
+    writer.write(&record_batch)?;
+    writer.close()?;
+    Ok(())
+}
+
+/// Converts a verified column to JSON values.
+///
+/// Integers wider than 32 bits, decimals, scalars and timestamps become strings so no precision
+/// is lost, and binary values become `0x`-prefixed hex strings. Decimals are scaled.
+fn owned_column_to_json_values<S: Scalar>(
+    column: OwnedColumn<S>,
+) -> Result<Vec<Value>, Box<dyn core::error::Error>> {
+    fn to_strings<T: ToString>(values: Vec<T>) -> Vec<Value> {
+        values
+            .iter()
+            .map(|value| Value::String(value.to_string()))
+            .collect()
+    }
+
+    Ok(match column {
+        OwnedColumn::Boolean(values) => values.into_iter().map(Value::from).collect(),
+        OwnedColumn::Uint8(values) => values.into_iter().map(Value::from).collect(),
+        OwnedColumn::TinyInt(values) => values.into_iter().map(Value::from).collect(),
+        OwnedColumn::SmallInt(values) => values.into_iter().map(Value::from).collect(),
+        OwnedColumn::Int(values) => values.into_iter().map(Value::from).collect(),
+        OwnedColumn::BigInt(values) | OwnedColumn::TimestampTZ(_, _, values) => to_strings(values),
+        OwnedColumn::Int128(values) => to_strings(values),
+        OwnedColumn::VarChar(values) => values.into_iter().map(Value::from).collect(),
+        OwnedColumn::VarBinary(values) => values
+            .iter()
+            .map(|value| Value::String(format!("0x{}", hex::encode(value))))
+            .collect(),
+        OwnedColumn::Decimal75(_, scale, values) => values
+            .iter()
+            .map(|value| Value::String(decimal_to_exact_string(value, scale)))
+            .collect(),
+        OwnedColumn::Scalar(values) => values
+            .iter()
+            .map(|value| Value::String(scalar_to_exact_string(value)))
+            .collect(),
+        column => Err(format!(
+            "unsupported column type for JSON output: {}",
+            column.column_type()
+        ))?,
+    })
+}
+
+/// Writes a verified table as JSON lines, one object per row, keeping only the first
+/// `limit_rows` rows if set.
+fn write_jsonl<S: Scalar>(
+    table: OwnedTable<S>,
+    limit_rows: Option<usize>,
+    out: &mut impl Write,
+) -> Result<(), Box<dyn core::error::Error>> {
+    let num_rows = limit_rows.map_or(table.num_rows(), |limit_rows| {
+        limit_rows.min(table.num_rows())
+    });
+    let columns = table
+        .into_inner()
+        .into_iter()
+        .map(|(ident, column)| Ok((ident.value, owned_column_to_json_values(column)?)))
+        .collect::<Result<Vec<_>, Box<dyn core::error::Error>>>()?;
+    for row in 0..num_rows {
+        let object: serde_json::Map<String, Value> = columns
+            .iter()
+            .map(|(name, values)| (name.clone(), values[row].clone()))
+            .collect();
+        writeln!(out, "{}", Value::Object(object))?;
+    }
+    Ok(())
+}
+
+/// Returns the first `limit_rows` rows of a record batch, or all of them if `limit_rows` is
+/// `None`.
+fn first_rows(record_batch: &RecordBatch, limit_rows: Option<usize>) -> RecordBatch {
+    let num_rows = limit_rows.map_or(record_batch.num_rows(), |limit_rows| {
+        limit_rows.min(record_batch.num_rows())
+    });
+    record_batch.slice(0, num_rows)
+}
+
 pub async fn query_and_verify(
     args: QueryAndVerifySdkArgs,
 ) -> Result<(), Box<dyn core::error::Error>> {
+    query_and_verify_to_writer(args, &mut std::io::stdout()).await
+}
+
+async fn query_and_verify_to_writer(
+    args: QueryAndVerifySdkArgs,
+    out: &mut impl Write,
+) -> Result<(), Box<dyn core::error::Error>> {
+    // A quiet run prints only the csv, so only `--explain-only` still prints the plan
+    if (args.explain && !args.quiet) || args.explain_only {
+        let plan = produce_plan(
+            args.zk_query_root_url.clone(),
+            args.auth_root_url.clone(),
+            &args.sxt_api_key,
+            &args.query,
+            &[],
+            args.source_network,
+        )
+        .await?;
+        writeln!(out, "Proof plan:\n{plan:?}")?;
+        if args.explain_only {
+            return Ok(());
+        }
+    }
+
     let (client, commitment_scheme) = (&args).into();
 
     // Execute the query and verify the result
-    let result: RecordBatch = client
-        .query_and_verify(
+    let CheckedResult {
+        table,
+        looks_truncated,
+    } = client
+        .query_and_verify_checked(
             &args.query,
             args.block_hash.map(|bh| bh.0),
             commitment_scheme,
         )
-        .await?
-        .try_into()?;
+        .await?;
 
     if let Some(path) = args.csv_file_path {
-        let cast_result = cast_record_batch_to_csv_friendly_record_batch(result.clone());
-        // Write to CSV
-        let mut file_write = std::fs::File::create(path)?;
-        let mut writer = WriterBuilder::new().build(&mut file_write);
-        writer.write(&cast_result)?;
+        write_csv(table.clone(), args.limit_rows, std::fs::File::create(path)?)?;
+    }
+    if let Some(path) = args.parquet_file_path {
+        write_parquet(table.clone(), args.limit_rows, std::fs::File::create(path)?)?;
+    }
+
+    if args.output_format == OutputFormat::Jsonl {
+        return match table {
+            DynOwnedTable::Dory(table) => write_jsonl(table, args.limit_rows, out),
+            #[cfg(feature = "hyperkzg")]
+            DynOwnedTable::BN(table) => write_jsonl(table, args.limit_rows, out),
+        };
+    }
+
+    if args.quiet {
+        write_csv(table, args.limit_rows, out)?;
+        return Ok(());
     }
 
+    let result = RecordBatch::try_from(table)?;
+    let shown = first_rows(&result, args.limit_rows);
+
     // Print the result of the query
-    println!("Query result:\n{}", pretty_format_batches(&[result])?);
+    writeln!(
+        out,
+        "Query result:\n{}",
+        pretty_format_batches(core::slice::from_ref(&shown))?
+    )?;
+    if shown.num_rows() < result.num_rows() {
+        writeln!(
+            out,
+            "(showing {} of {} rows)",
+            shown.num_rows(),
+            result.num_rows()
+        )?;
+    }
+    // Only the human-readable output has room for a warning
+    if looks_truncated {
+        writeln!(
+            out,
+            "Warning: the query returned {} rows, fewer than the table it selects from; the result may have been truncated by an implicit row limit",
+            result.num_rows()
+        )?;
+    }
     Ok(())
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::query_and_verify::cast_record_batch_to_csv_friendly_record_batch;
-    use datafusion::arrow::array::{
-        ArrayRef, BinaryArray, FixedSizeBinaryArray, LargeBinaryArray, RecordBatch, StringArray,
+    #[cfg(feature = "hyperkzg")]
+    use super::{query_and_verify_to_writer, OutputFormat, QueryAndVerifySdkArgs};
+    #[cfg(feature = "hyperkzg")]
+    use crate::{
+        base::zk_query_models::SxtNetwork,
+        native::mock_server::{
+            fixture_routes, mock_server, query_and_verify_args, request_lines, RecordedRequests,
+        },
+    };
+    use crate::{
+        native::DynOwnedTable,
+        query_and_verify::{
+            cast_record_batch_to_csv_friendly_record_batch, decimal_to_exact_string, first_rows,
+            write_csv, write_jsonl, write_parquet,
+        },
+    };
+    use arrow_csv::WriterBuilder;
+    use datafusion::arrow::{
+        array::{
+            ArrayRef, BinaryArray, DictionaryArray, FixedSizeBinaryArray, LargeBinaryArray,
+            LargeStringArray, RecordBatch, StringArray,
+        },
+        datatypes::Int32Type,
+        util::pretty::pretty_format_batches,
     };
+    use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+    use proof_of_sql::{
+        base::{
+            database::{OwnedColumn, OwnedTable},
+            math::decimal::Precision,
+        },
+        proof_primitive::dory::DoryScalar,
+    };
+    use sqlparser::ast::Ident;
     use std::sync::Arc;
 
     #[test]
@@ -214,4 +542,410 @@ mod tests {
         .unwrap();
         assert_eq!(cast_record_batch, expected_record_batch);
     }
+
+    #[test]
+    fn we_can_cast_fixed_size_binary_columns_of_different_widths() {
+        let address = [0xab; 20];
+        let hash = [0xcd; 32];
+        let addresses: ArrayRef = Arc::new(
+            FixedSizeBinaryArray::try_from_sparse_iter_with_size(
+                [Some(address), None].into_iter(),
+                20,
+            )
+            .unwrap(),
+        );
+        let hashes: ArrayRef = Arc::new(
+            FixedSizeBinaryArray::try_from_sparse_iter_with_size(
+                [Some(hash), Some(hash)].into_iter(),
+                32,
+            )
+            .unwrap(),
+        );
+        let nullable_binary: ArrayRef =
+            Arc::new(BinaryArray::from_opt_vec(vec![None, Some(&[1, 2][..])]));
+        let record_batch = RecordBatch::try_from_iter(vec![
+            ("address", addresses),
+            ("hash", hashes),
+            ("data", nullable_binary),
+        ])
+        .unwrap();
+
+        let cast_record_batch = cast_record_batch_to_csv_friendly_record_batch(record_batch);
+        let expected_record_batch = RecordBatch::try_from_iter(vec![
+            (
+                "address",
+                Arc::new(StringArray::from(vec![Some("ab".repeat(20)), None])) as ArrayRef,
+            ),
+            (
+                "hash",
+                Arc::new(StringArray::from(vec!["cd".repeat(32), "cd".repeat(32)])),
+            ),
+            (
+                "data",
+                Arc::new(StringArray::from(vec![None, Some("0102")])),
+            ),
+        ])
+        .unwrap();
+        assert_eq!(cast_record_batch, expected_record_batch);
+    }
+
+    #[test]
+    fn we_can_flatten_dictionary_and_large_string_columns_for_csv() {
+        let dictionary_array: ArrayRef = Arc::new(
+            ["ETHEREUM", "BITCOIN", "ETHEREUM"]
+                .into_iter()
+                .collect::<DictionaryArray<Int32Type>>(),
+        );
+        let large_string_array: ArrayRef = Arc::new(LargeStringArray::from(vec!["a", "b", "c"]));
+        let record_batch = RecordBatch::try_from_iter(vec![
+            ("chain", dictionary_array),
+            ("letter", large_string_array),
+        ])
+        .unwrap();
+
+        let cast_record_batch = cast_record_batch_to_csv_friendly_record_batch(record_batch);
+        let expected_record_batch = RecordBatch::try_from_iter(vec![
+            (
+                "chain",
+                Arc::new(StringArray::from(vec!["ETHEREUM", "BITCOIN", "ETHEREUM"])) as ArrayRef,
+            ),
+            ("letter", Arc::new(StringArray::from(vec!["a", "b", "c"]))),
+        ])
+        .unwrap();
+        assert_eq!(cast_record_batch, expected_record_batch);
+
+        let mut csv = Vec::new();
+        WriterBuilder::new()
+            .build(&mut csv)
+            .write(&cast_record_batch)
+            .unwrap();
+        assert_eq!(
+            String::from_utf8(csv).unwrap(),
+            "chain,letter\nETHEREUM,a\nBITCOIN,b\nETHEREUM,c\n"
+        );
+    }
+
+    #[test]
+    fn we_can_format_decimals_exactly() {
+        let decimal = |value: i128, scale| decimal_to_exact_string(&DoryScalar::from(value), scale);
+        assert_eq!(decimal(-12345, 2), "-123.45");
+        assert_eq!(decimal(5, 3), "0.005");
+        assert_eq!(decimal(-5, 3), "-0.005");
+        assert_eq!(decimal(0, 2), "0.00");
+        assert_eq!(decimal(12, -2), "1200");
+        assert_eq!(decimal(0, -2), "0");
+    }
+
+    #[test]
+    fn we_can_write_decimal_and_scalar_columns_to_csv_with_full_precision() {
+        let value: i128 = 123_456_789_012_345_678_901_234_567_890_123_456_789;
+        let table = DynOwnedTable::Dory(
+            OwnedTable::try_new(
+                [
+                    (
+                        Ident::new("AMOUNT"),
+                        OwnedColumn::Decimal75(
+                            Precision::new(75).unwrap(),
+                            18,
+                            vec![DoryScalar::from(value), DoryScalar::from(-value)],
+                        ),
+                    ),
+                    (
+                        Ident::new("RAW"),
+                        OwnedColumn::Scalar(vec![DoryScalar::from(value), DoryScalar::from(-1)]),
+                    ),
+                ]
+                .into_iter()
+                .collect(),
+            )
+            .unwrap(),
+        );
+
+        let mut csv = Vec::new();
+        write_csv(table, None, &mut csv).unwrap();
+
+        assert_eq!(
+            String::from_utf8(csv).unwrap(),
+            "AMOUNT,RAW\n\
+             123456789012345678901.234567890123456789,123456789012345678901234567890123456789\n\
+             -123456789012345678901.234567890123456789,-1\n"
+        );
+    }
+
+    #[test]
+    fn we_write_only_the_header_of_an_empty_table_to_csv() {
+        let table = DynOwnedTable::Dory(
+            OwnedTable::try_new(
+                [
+                    (Ident::new("BLOCK_NUMBER"), OwnedColumn::BigInt(vec![])),
+                    (
+                        Ident::new("AMOUNT"),
+                        OwnedColumn::Decimal75(Precision::new(10).unwrap(), 2, vec![]),
+                    ),
+                ]
+                .into_iter()
+                .collect(),
+            )
+            .unwrap(),
+        );
+
+        let mut csv = Vec::new();
+        write_csv(table.clone(), None, &mut csv).unwrap();
+        assert_eq!(String::from_utf8(csv).unwrap(), "BLOCK_NUMBER,AMOUNT\n");
+
+        let shown = pretty_format_batches(&[RecordBatch::try_from(table).unwrap()])
+            .unwrap()
+            .to_string();
+        assert!(shown.contains("BLOCK_NUMBER"));
+        assert!(shown.contains("AMOUNT"));
+    }
+
+    /// Arguments for running a query against a mock auth service and ZK Query API that return
+    /// the fixture response.
+    #[cfg(feature = "hyperkzg")]
+    fn mock_args(explain: bool, explain_only: bool) -> (QueryAndVerifySdkArgs, RecordedRequests) {
+        let (base_url, requests) = mock_server(fixture_routes());
+        let args = QueryAndVerifySdkArgs {
+            explain,
+            explain_only,
+            ..query_and_verify_args(
+                &base_url,
+                "SELECT block_number FROM ethereum.blocks GROUP BY block_number",
+            )
+        };
+        (args, requests)
+    }
+
+    #[cfg(feature = "hyperkzg")]
+    #[tokio::test]
+    async fn we_print_the_plan_before_the_result_when_explaining() {
+        let (args, _) = mock_args(true, false);
+        let mut out = Vec::new();
+        query_and_verify_to_writer(args, &mut out).await.unwrap();
+
+        let out = String::from_utf8(out).unwrap();
+        let plan_position = out.find("Proof plan:").unwrap();
+        let result_position = out.find("Query result:").unwrap();
+        assert!(plan_position < result_position);
+        assert!(out.contains("22432845"));
+    }
+
+    #[cfg(feature = "hyperkzg")]
+    #[tokio::test]
+    async fn we_only_print_the_plan_when_explaining_only() {
+        let (args, requests) = mock_args(false, true);
+        let mut out = Vec::new();
+        query_and_verify_to_writer(args, &mut out).await.unwrap();
+
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.starts_with("Proof plan:"));
+        assert!(!out.contains("Query result:"));
+        assert!(!request_lines(&requests).contains(&"POST /v1/zkquery HTTP/1.1".to_string()));
+    }
+
+    #[cfg(feature = "hyperkzg")]
+    #[tokio::test]
+    async fn we_submit_the_query_for_the_source_network() {
+        let (args, requests) = mock_args(false, false);
+        let args = QueryAndVerifySdkArgs {
+            source_network: SxtNetwork::Testnet,
+            ..args
+        };
+        query_and_verify_to_writer(args, &mut Vec::new())
+            .await
+            .unwrap();
+
+        let requests = requests.lock().unwrap();
+        let (_, submit_body) = requests
+            .iter()
+            .find(|(request_line, _)| request_line.starts_with("POST /v1/zkquery "))
+            .unwrap();
+        let submit_body: serde_json::Value = serde_json::from_str(submit_body).unwrap();
+        assert_eq!(submit_body["sourceNetwork"], "testnet");
+    }
+
+    #[cfg(feature = "hyperkzg")]
+    #[tokio::test]
+    async fn we_truncate_the_output_but_not_the_verified_result() {
+        let (args, _) = mock_args(false, false);
+        let csv_file_path =
+            std::env::temp_dir().join(format!("limit-rows-{}.csv", std::process::id()));
+        let args = QueryAndVerifySdkArgs {
+            limit_rows: Some(0),
+            csv_file_path: Some(csv_file_path.clone()),
+            ..args
+        };
+        let mut out = Vec::new();
+        query_and_verify_to_writer(args, &mut out).await.unwrap();
+
+        // The fixture result has a single verified row, which is counted but not shown
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.contains("(showing 0 of 1 rows)"));
+        assert!(!out.contains("22432845"));
+        let csv = std::fs::read_to_string(&csv_file_path).unwrap();
+        std::fs::remove_file(csv_file_path).unwrap();
+        assert_eq!(csv.lines().count(), 1);
+    }
+
+    #[cfg(feature = "hyperkzg")]
+    #[tokio::test]
+    async fn we_warn_when_the_result_looks_truncated() {
+        let (args, _) = mock_args(false, false);
+        let mut out = Vec::new();
+        query_and_verify_to_writer(args.clone(), &mut out)
+            .await
+            .unwrap();
+        assert!(!String::from_utf8(out).unwrap().contains("Warning:"));
+
+        // The fixture result has one row, fewer than ETHEREUM.BLOCKS holds
+        let args = QueryAndVerifySdkArgs {
+            query: "SELECT block_number FROM ethereum.blocks".to_string(),
+            ..args
+        };
+        let mut out = Vec::new();
+        query_and_verify_to_writer(args, &mut out).await.unwrap();
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.contains("22432845"));
+        assert!(out
+            .contains("Warning: the query returned 1 rows, fewer than the table it selects from"));
+    }
+
+    #[test]
+    fn we_can_write_a_table_as_parquet_and_read_it_back() {
+        let table = DynOwnedTable::Dory(
+            OwnedTable::try_new(
+                [
+                    (Ident::new("ID"), OwnedColumn::BigInt(vec![1, 2, i64::MAX])),
+                    (
+                        Ident::new("NAME"),
+                        OwnedColumn::VarChar(vec!["a".to_string(), String::new(), "c".to_string()]),
+                    ),
+                    (
+                        Ident::new("DATA"),
+                        OwnedColumn::VarBinary(vec![vec![0xab], vec![], vec![1, 2]]),
+                    ),
+                ]
+                .into_iter()
+                .collect(),
+            )
+            .unwrap(),
+        );
+        let path = std::env::temp_dir().join(format!("result-{}.parquet", std::process::id()));
+
+        write_parquet(table.clone(), None, std::fs::File::create(&path).unwrap()).unwrap();
+
+        let read_batches =
+            ParquetRecordBatchReaderBuilder::try_new(std::fs::File::open(&path).unwrap())
+                .unwrap()
+                .build()
+                .unwrap()
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap();
+        std::fs::remove_file(path).unwrap();
+        assert_eq!(read_batches, vec![RecordBatch::try_from(table).unwrap()]);
+    }
+
+    #[test]
+    fn we_keep_every_row_without_a_limit() {
+        let batch = RecordBatch::try_from_iter(vec![(
+            "a",
+            Arc::new(StringArray::from(vec!["x", "y"])) as ArrayRef,
+        )])
+        .unwrap();
+        assert_eq!(first_rows(&batch, None), batch);
+        assert_eq!(first_rows(&batch, Some(5)), batch);
+        assert_eq!(first_rows(&batch, Some(1)), batch.slice(0, 1));
+    }
+
+    #[test]
+    fn we_can_write_a_table_as_json_lines() {
+        let table = OwnedTable::<DoryScalar>::try_new(
+            [
+                (Ident::new("ID"), OwnedColumn::BigInt(vec![1, 2, i64::MAX])),
+                (
+                    Ident::new("FLAG"),
+                    OwnedColumn::Boolean(vec![true, false, true]),
+                ),
+                (
+                    Ident::new("AMOUNT"),
+                    OwnedColumn::Decimal75(
+                        Precision::new(10).unwrap(),
+                        2,
+                        [150, -25, 0].map(DoryScalar::from).to_vec(),
+                    ),
+                ),
+                (
+                    Ident::new("DATA"),
+                    OwnedColumn::VarBinary(vec![vec![0xab], vec![], vec![1, 2]]),
+                ),
+            ]
+            .into_iter()
+            .collect(),
+        )
+        .unwrap();
+        let mut out = Vec::new();
+        write_jsonl(table, None, &mut out).unwrap();
+
+        let out = String::from_utf8(out).unwrap();
+        let rows: Vec<serde_json::Value> = out
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(rows.len(), 3);
+        for row in &rows {
+            let mut keys: Vec<_> = row.as_object().unwrap().keys().collect();
+            keys.sort();
+            assert_eq!(keys, ["AMOUNT", "DATA", "FLAG", "ID"]);
+        }
+        assert_eq!(
+            rows[2],
+            serde_json::json!({
+                "ID": "9223372036854775807",
+                "FLAG": true,
+                "AMOUNT": "0.00",
+                "DATA": "0x0102",
+            })
+        );
+        assert_eq!(rows[1]["AMOUNT"], "-0.25");
+    }
+
+    #[cfg(feature = "hyperkzg")]
+    #[tokio::test]
+    async fn we_only_print_json_lines_in_jsonl_mode() {
+        let (args, _) = mock_args(false, false);
+        let args = QueryAndVerifySdkArgs {
+            output_format: OutputFormat::Jsonl,
+            ..args
+        };
+        let mut out = Vec::new();
+        query_and_verify_to_writer(args, &mut out).await.unwrap();
+
+        let out = String::from_utf8(out).unwrap();
+        assert!(!out.contains("Query result:"));
+        let rows: Vec<serde_json::Value> = out
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0]["BLOCK_NUMBER"], "22432845");
+    }
+
+    #[cfg(feature = "hyperkzg")]
+    #[tokio::test]
+    async fn we_only_print_csv_when_quiet() {
+        // Even when explaining
+        let (args, _) = mock_args(true, false);
+        let args = QueryAndVerifySdkArgs {
+            quiet: true,
+            ..args
+        };
+        let mut out = Vec::new();
+        query_and_verify_to_writer(args, &mut out).await.unwrap();
+
+        let out = String::from_utf8(out).unwrap();
+        let lines: Vec<_> = out.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].starts_with("BLOCK_NUMBER"));
+        assert!(lines[1].starts_with("22432845"));
+    }
 }
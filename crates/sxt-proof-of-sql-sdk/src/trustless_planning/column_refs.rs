@@ -0,0 +1,309 @@
+use crate::base::IdentCasing;
+use core::ops::ControlFlow;
+use indexmap::{IndexMap, IndexSet};
+use proof_of_sql::base::database::TableRef;
+use snafu::Snafu;
+use sqlparser::ast::{Expr, Ident, ObjectName, Query, Statement, TableFactor, Visit, Visitor};
+
+/// Errors that can occur when extracting the columns referenced by a query.
+#[derive(Snafu, Debug, PartialEq, Eq)]
+pub enum ColumnRefError {
+    /// The table name has more parts than `schema.table`.
+    #[snafu(display("unsupported table name: {name}"))]
+    UnsupportedTableName { name: String },
+    /// A qualified column refers to a table that is not in the query.
+    #[snafu(display("column {column} refers to a table that is not in the query"))]
+    UnresolvedQualifier { column: String },
+    /// An unqualified column could belong to more than one table.
+    #[snafu(display("column {column} is ambiguous"))]
+    AmbiguousColumn { column: String },
+}
+
+/// Collects table factors, common table expression names and column identifiers so they can be
+/// resolved once the whole statement has been visited.
+struct ColumnRefCollector {
+    casing: IdentCasing,
+    /// Table names with their aliases, in the order they appear.
+    table_factors: Vec<(ObjectName, Option<Ident>)>,
+    /// Normalized names of the common table expressions defined at any level of the query.
+    cte_names: IndexSet<Ident>,
+    columns: Vec<Vec<Ident>>,
+}
+
+/// The tables of a statement, keyed by every name they can be qualified with.
+#[derive(Default)]
+struct ResolvedTables {
+    /// Normalized table refs keyed by every name they can be qualified with.
+    tables: IndexMap<String, TableRef>,
+    /// Names that qualify a common table expression rather than a table.
+    cte_qualifiers: IndexSet<String>,
+}
+
+impl ColumnRefCollector {
+    fn new(casing: IdentCasing) -> Self {
+        Self {
+            casing,
+            table_factors: Vec::new(),
+            cte_names: IndexSet::new(),
+            columns: Vec::new(),
+        }
+    }
+
+    /// Resolve the collected table factors, skipping references to common table expressions.
+    fn resolve_tables(&self) -> Result<ResolvedTables, ColumnRefError> {
+        let mut resolved = ResolvedTables::default();
+        for (name, alias) in &self.table_factors {
+            let alias = alias.as_ref().map(|alias| self.casing.apply(alias).value);
+            let table_ref = match name.0.as_slice() {
+                [table_id] if self.cte_names.contains(&self.casing.apply(table_id)) => {
+                    resolved
+                        .cte_qualifiers
+                        .insert(self.casing.apply(table_id).value);
+                    resolved.cte_qualifiers.extend(alias);
+                    continue;
+                }
+                [table_id] => TableRef::from_idents(None, table_id.clone()),
+                [schema_id, table_id] => {
+                    TableRef::from_idents(Some(schema_id.clone()), table_id.clone())
+                }
+                _ => {
+                    return Err(ColumnRefError::UnsupportedTableName {
+                        name: name.to_string(),
+                    })
+                }
+            };
+            let table_ref = self.casing.apply_to_table_ref(&table_ref);
+            let mut qualifiers = vec![table_ref.to_string(), table_ref.table_id().value.clone()];
+            qualifiers.extend(alias);
+            for qualifier in qualifiers {
+                resolved.tables.insert(qualifier, table_ref.clone());
+            }
+        }
+        Ok(resolved)
+    }
+}
+
+impl Visitor for ColumnRefCollector {
+    type Break = ();
+
+    fn pre_visit_query(&mut self, query: &Query) -> ControlFlow<()> {
+        let cte_names = query
+            .with
+            .iter()
+            .flat_map(|with| &with.cte_tables)
+            .map(|cte| self.casing.apply(&cte.alias.name));
+        self.cte_names.extend(cte_names);
+        ControlFlow::Continue(())
+    }
+
+    fn pre_visit_table_factor(&mut self, table_factor: &TableFactor) -> ControlFlow<()> {
+        if let TableFactor::Table { name, alias, .. } = table_factor {
+            self.table_factors
+                .push((name.clone(), alias.as_ref().map(|alias| alias.name.clone())));
+        }
+        ControlFlow::Continue(())
+    }
+
+    fn pre_visit_expr(&mut self, expr: &Expr) -> ControlFlow<()> {
+        match expr {
+            Expr::Identifier(ident) => self.columns.push(vec![ident.clone()]),
+            Expr::CompoundIdentifier(idents) => self.columns.push(idents.clone()),
+            _ => {}
+        }
+        ControlFlow::Continue(())
+    }
+}
+
+/// Get the columns referenced anywhere in a statement, grouped by the table they belong to.
+///
+/// This includes columns only used in filters, grouping or ordering, so the result is the full set
+/// of column commitments needed to plan and verify the query. Every table the query reads is a
+/// key, even if none of its columns are named. Common table expressions defined at any level of
+/// the query are not tables, so they and their columns are skipped. Table refs and columns are
+/// normalized with `casing`.
+///
+/// An unqualified column belongs to the one table for which `has_column` returns `true`. It is
+/// skipped if no table has it, e.g. because it names a column alias.
+pub fn get_column_refs_from_statement(
+    statement: &Statement,
+    casing: IdentCasing,
+    has_column: impl Fn(&TableRef, &Ident) -> bool,
+) -> Result<IndexMap<TableRef, IndexSet<Ident>>, ColumnRefError> {
+    let mut collector = ColumnRefCollector::new(casing);
+    let _ = statement.visit(&mut collector);
+    let ResolvedTables {
+        tables,
+        cte_qualifiers,
+    } = collector.resolve_tables()?;
+
+    let distinct_tables: IndexSet<&TableRef> = tables.values().collect();
+    let mut column_refs: IndexMap<TableRef, IndexSet<Ident>> = distinct_tables
+        .iter()
+        .map(|table_ref| ((*table_ref).clone(), IndexSet::new()))
+        .collect();
+
+    for idents in &collector.columns {
+        let Some((column_id, qualifier)) = idents.split_last() else {
+            continue;
+        };
+        let column_id = casing.apply(column_id);
+        let table_ref = if qualifier.is_empty() {
+            let mut candidates = distinct_tables
+                .iter()
+                .filter(|table_ref| has_column(table_ref, &column_id));
+            match (candidates.next(), candidates.next()) {
+                (None, _) => continue,
+                (Some(table_ref), None) => (*table_ref).clone(),
+                (Some(_), Some(_)) => {
+                    return Err(ColumnRefError::AmbiguousColumn {
+                        column: column_id.value,
+                    })
+                }
+            }
+        } else {
+            let qualifier = qualifier
+                .iter()
+                .map(|ident| casing.apply(ident).value)
+                .collect::<Vec<_>>()
+                .join(".");
+            if cte_qualifiers.contains(&qualifier) {
+                continue;
+            }
+            tables
+                .get(&qualifier)
+                .cloned()
+                .ok_or_else(|| ColumnRefError::UnresolvedQualifier {
+                    column: format!("{qualifier}.{}", column_id.value),
+                })?
+        };
+        column_refs.entry(table_ref).or_default().insert(column_id);
+    }
+
+    Ok(column_refs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indexmap::{indexmap, indexset};
+    use sqlparser::{dialect::GenericDialect, parser::Parser};
+
+    fn column_refs(sql: &str) -> Result<IndexMap<TableRef, IndexSet<Ident>>, ColumnRefError> {
+        get_column_refs_from_statement(
+            &Parser::parse_sql(&GenericDialect {}, sql).unwrap()[0],
+            IdentCasing::Upper,
+            |_, _| true,
+        )
+    }
+
+    #[test]
+    fn we_can_get_column_refs_including_filter_columns() {
+        assert_eq!(
+            column_refs("SELECT a, b FROM t WHERE c > 1").unwrap(),
+            indexmap! {
+                "T".parse::<TableRef>().unwrap() => indexset! {
+                    Ident::new("A"),
+                    Ident::new("B"),
+                    Ident::new("C"),
+                },
+            }
+        );
+    }
+
+    #[test]
+    fn we_can_get_column_refs_from_qualified_columns_across_joins() {
+        assert_eq!(
+            column_refs(
+                "SELECT b.number, t.hash FROM ethereum.blocks b \
+                 JOIN ethereum.transactions t ON b.number = t.block_number"
+            )
+            .unwrap(),
+            indexmap! {
+                "ETHEREUM.BLOCKS".parse::<TableRef>().unwrap() => indexset! {
+                    Ident::new("NUMBER"),
+                },
+                "ETHEREUM.TRANSACTIONS".parse::<TableRef>().unwrap() => indexset! {
+                    Ident::new("HASH"),
+                    Ident::new("BLOCK_NUMBER"),
+                },
+            }
+        );
+    }
+
+    #[test]
+    fn we_can_resolve_unqualified_columns_against_the_tables_that_have_them() {
+        let statement = Parser::parse_sql(
+            &GenericDialect {},
+            "SELECT a, c AS x FROM s.t1 JOIN s.t2 ON t1.b = t2.b ORDER BY x",
+        )
+        .unwrap()
+        .remove(0);
+        let t1 = TableRef::from_names(Some("s"), "t1");
+        assert_eq!(
+            get_column_refs_from_statement(
+                &statement,
+                IdentCasing::Preserve,
+                |table_ref, column| {
+                    (*table_ref == t1 && column.value == "a") || column.value == "c"
+                }
+            ),
+            Err(ColumnRefError::AmbiguousColumn {
+                column: "c".to_string()
+            })
+        );
+        assert_eq!(
+            get_column_refs_from_statement(
+                &statement,
+                IdentCasing::Preserve,
+                |table_ref, column| { *table_ref == t1 && column.value != "x" }
+            )
+            .unwrap(),
+            indexmap! {
+                t1 => indexset! { Ident::new("a"), Ident::new("c"), Ident::new("b") },
+                TableRef::from_names(Some("s"), "t2") => indexset! { Ident::new("b") },
+            }
+        );
+    }
+
+    #[test]
+    fn we_skip_common_table_expressions_at_every_query_level() {
+        assert_eq!(
+            column_refs(
+                "WITH outer_cte AS (SELECT t.a FROM s.t) \
+                 SELECT o.a FROM outer_cte o WHERE o.a IN \
+                 (WITH inner_cte AS (SELECT u.b FROM s.u) SELECT inner_cte.b FROM inner_cte)"
+            )
+            .unwrap(),
+            indexmap! {
+                "S.T".parse::<TableRef>().unwrap() => indexset! { Ident::new("A") },
+                "S.U".parse::<TableRef>().unwrap() => indexset! { Ident::new("B") },
+            }
+        );
+    }
+
+    #[test]
+    fn we_cannot_get_column_refs_for_tables_with_more_than_two_name_parts() {
+        assert_eq!(
+            column_refs("SELECT a FROM db.s.t"),
+            Err(ColumnRefError::UnsupportedTableName {
+                name: "db.s.t".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn we_cannot_get_column_refs_for_ambiguous_or_unresolved_columns() {
+        assert_eq!(
+            column_refs("SELECT a FROM s.t1 JOIN s.t2 ON t1.x = t2.x"),
+            Err(ColumnRefError::AmbiguousColumn {
+                column: "A".to_string()
+            })
+        );
+        assert_eq!(
+            column_refs("SELECT u.a FROM s.t"),
+            Err(ColumnRefError::UnresolvedQualifier {
+                column: "U.A".to_string()
+            })
+        );
+    }
+}
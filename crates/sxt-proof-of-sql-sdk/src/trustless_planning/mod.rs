@@ -1 +1,5 @@
+mod column_refs;
+pub(crate) use column_refs::{get_column_refs_from_statement, ColumnRefError};
 mod prover_query;
+#[cfg(feature = "native")]
+pub(crate) use prover_query::produce_plan_trustlessly;
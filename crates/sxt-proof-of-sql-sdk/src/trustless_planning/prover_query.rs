@@ -1,5 +1,7 @@
-use crate::base::{CommitmentEvaluationProofId, UppercaseAccessor};
+use super::{get_column_refs_from_statement, ColumnRefError};
+use crate::base::{CaseAccessor, CommitmentEvaluationProofId, IdentCasing, ParseSqlError};
 use datafusion::config::ConfigOptions;
+use itertools::Itertools;
 #[cfg(feature = "native")]
 use proof_of_sql::proof_primitive::dory::{DynamicDoryCommitment, DynamicDoryEvaluationProof};
 #[cfg(feature = "hyperkzg")]
@@ -7,7 +9,10 @@ use proof_of_sql::proof_primitive::hyperkzg::{
     HyperKZGCommitment, HyperKZGCommitmentEvaluationProof,
 };
 use proof_of_sql::{
-    base::commitment::{CommitmentEvaluationProof, QueryCommitments},
+    base::{
+        commitment::{Commitment, CommitmentEvaluationProof, QueryCommitments},
+        database::TableRef,
+    },
     sql::proof_plans::DynProofPlan,
 };
 use proof_of_sql_planner::{
@@ -22,12 +27,45 @@ pub enum PlanProverQueryError {
     /// Unable to parse sql.
     #[snafu(display("unable to parse sql: {source}"), context(false))]
     ParseIdentifier { source: ParserError },
+    /// The sql is not valid.
+    #[snafu(display("unable to parse sql: {source}"))]
+    Parse { source: ParserError },
     /// Unable to serialize proof plan.
     #[snafu(display("unable to serialize proof plan: {error}"))]
     ProofPlanSerialization { error: bincode::error::EncodeError },
     /// Planner was unable to generate proof plan
     #[snafu(display("unable to produce plan: {source}"), context(false))]
     ProofPlanGeneration { source: PlannerError },
+    /// The sql contained no statements.
+    #[snafu(display("query contains no sql statements"))]
+    EmptyQuery,
+    /// The sql contained more than one statement.
+    #[snafu(display("expected a single sql statement, found {count}"))]
+    MultipleStatements { count: usize },
+    /// Planner did not produce a plan for the statement.
+    #[snafu(display("planner did not produce a proof plan"))]
+    MissingProofPlan,
+    /// The columns the query references could not be resolved.
+    #[snafu(display("unable to resolve query columns: {source}"), context(false))]
+    ColumnRefs { source: ColumnRefError },
+    /// The query references tables that have no commitments.
+    ///
+    /// Every missing table is listed, rather than only the first, so a query against several
+    /// unindexed tables can be fixed in one go.
+    #[snafu(display("tables not found: {}", tables.iter().join(", ")))]
+    TableNotFound { tables: Vec<TableRef> },
+}
+
+impl From<ParseSqlError> for PlanProverQueryError {
+    fn from(error: ParseSqlError) -> Self {
+        match error {
+            ParseSqlError::Parse { error } => PlanProverQueryError::Parse { source: error },
+            ParseSqlError::EmptyQuery => PlanProverQueryError::EmptyQuery,
+            ParseSqlError::MultipleStatements { count } => {
+                PlanProverQueryError::MultipleStatements { count }
+            }
+        }
+    }
 }
 
 impl From<bincode::error::EncodeError> for PlanProverQueryError {
@@ -37,17 +75,72 @@ impl From<bincode::error::EncodeError> for PlanProverQueryError {
 }
 
 /// Create a query for the prover service from sql query text and commitments.
+///
+/// Identifiers are uppercased to match the commitments stored on sxt-chain.
 pub fn produce_plan_trustlessly<CPI: CommitmentEvaluationProofId>(
     query: &Statement,
     commitments: &QueryCommitments<<CPI as CommitmentEvaluationProof>::Commitment>,
 ) -> Result<DynProofPlan, PlanProverQueryError> {
-    let accessor = &UppercaseAccessor(commitments);
-    let query = statement_with_uppercase_identifiers(query.clone());
+    produce_plan_trustlessly_with_casing::<CPI>(query, commitments, IdentCasing::Upper)
+}
+
+/// The commitments of only the tables `query` references, normalized with `casing`.
+///
+/// Fails with [`PlanProverQueryError::TableNotFound`] if any referenced table has no commitment.
+fn referenced_commitments<C: Commitment>(
+    query: &Statement,
+    commitments: &QueryCommitments<C>,
+    casing: IdentCasing,
+) -> Result<QueryCommitments<C>, PlanProverQueryError> {
+    let column_refs = get_column_refs_from_statement(query, casing, |table_ref, column_id| {
+        commitments.get(table_ref).is_some_and(|commitment| {
+            commitment
+                .column_commitments()
+                .column_metadata()
+                .contains_key(column_id)
+        })
+    })?;
+    let tables: Vec<TableRef> = column_refs
+        .keys()
+        .filter(|table_ref| !commitments.contains_key(*table_ref))
+        .cloned()
+        .collect();
+    if !tables.is_empty() {
+        return Err(PlanProverQueryError::TableNotFound { tables });
+    }
+    Ok(column_refs
+        .keys()
+        .map(|table_ref| (table_ref.clone(), commitments[table_ref].clone()))
+        .collect())
+}
+
+/// Create a query for the prover service from sql query text and commitments, normalizing
+/// identifiers with `casing`.
+///
+/// The commitments must be keyed in the same casing. Every table the query references must have
+/// a commitment, otherwise [`PlanProverQueryError::TableNotFound`] lists the missing ones. The
+/// query is planned against the commitments of only the tables it references.
+pub fn produce_plan_trustlessly_with_casing<CPI: CommitmentEvaluationProofId>(
+    query: &Statement,
+    commitments: &QueryCommitments<<CPI as CommitmentEvaluationProof>::Commitment>,
+    casing: IdentCasing,
+) -> Result<DynProofPlan, PlanProverQueryError> {
+    let commitments = referenced_commitments(query, commitments, casing)?;
+    let query = match casing {
+        IdentCasing::Upper => statement_with_uppercase_identifiers(query.clone()),
+        IdentCasing::Lower | IdentCasing::Preserve => query.clone(),
+    };
     let mut config_options = ConfigOptions::default();
-    config_options.sql_parser.enable_ident_normalization = false;
-    let proof_plan =
-        sql_to_proof_plans(core::slice::from_ref(&query), accessor, &config_options)?[0].clone();
-    Ok(proof_plan)
+    // The planner's own normalization lowercases unquoted identifiers
+    config_options.sql_parser.enable_ident_normalization = casing == IdentCasing::Lower;
+    sql_to_proof_plans(
+        core::slice::from_ref(&query),
+        &CaseAccessor::new(&commitments, casing),
+        &config_options,
+    )?
+    .into_iter()
+    .next()
+    .ok_or(PlanProverQueryError::MissingProofPlan)
 }
 
 /// Create a query for the prover service from sql query text and Dynamic Dory commitments.
@@ -73,14 +166,23 @@ pub fn produce_hyperkzg_plan_trustlessly(
 #[cfg(feature = "native")]
 #[cfg(test)]
 mod tests {
-    use crate::trustless_planning::prover_query::produce_dory_plan_trustlessly;
+    use crate::{
+        base::{parse_single_statement, IdentCasing},
+        trustless_planning::{
+            prover_query::{
+                produce_dory_plan_trustlessly, produce_plan_trustlessly_with_casing,
+                referenced_commitments, PlanProverQueryError,
+            },
+            ColumnRefError,
+        },
+    };
     use ark_std::test_rng;
     use bumpalo::Bump;
     use proof_of_sql::{
         base::{
             commitment::{QueryCommitments, QueryCommitmentsExt},
             database::{
-                table_utility::{borrowed_decimal75, table},
+                table_utility::{borrowed_bigint, borrowed_decimal75, table},
                 ColumnRef, ColumnType, TableRef, TableTestAccessor,
             },
             math::decimal::Precision,
@@ -88,6 +190,7 @@ mod tests {
         proof_primitive::dory::{
             DoryScalar, DynamicDoryEvaluationProof, ProverSetup, PublicParameters,
         },
+        sql::proof::ProofPlan,
     };
     use sqlparser::{dialect::GenericDialect, parser::Parser};
 
@@ -127,4 +230,174 @@ mod tests {
         );
         produce_dory_plan_trustlessly(&query_parsed, &query_commitments).unwrap();
     }
+
+    #[test]
+    fn we_can_plan_the_same_query_against_differently_cased_tables() {
+        let sql = r"SELECT a FROM tab;";
+        let query_parsed = Parser::parse_sql(&GenericDialect {}, sql).unwrap()[0].clone();
+        let uppercase_table_ref = TableRef::from_names(None, "TAB");
+        let lowercase_table_ref = TableRef::from_names(None, "tab");
+        let alloc = Bump::new();
+        let public_parameters = PublicParameters::test_rand(5, &mut test_rng());
+        let prover_setup = ProverSetup::from(&public_parameters);
+        let mut accessor = TableTestAccessor::<DynamicDoryEvaluationProof>::new_from_table(
+            uppercase_table_ref.clone(),
+            table::<DoryScalar>(vec![borrowed_bigint("A", [1, 2], &alloc)]),
+            0,
+            &prover_setup,
+        );
+        accessor.add_table(
+            lowercase_table_ref.clone(),
+            table::<DoryScalar>(vec![borrowed_bigint("a", [3, 4], &alloc)]),
+            0,
+        );
+        let query_commitments = QueryCommitments::from_accessor_with_max_bounds(
+            vec![
+                ColumnRef::new(uppercase_table_ref.clone(), "A".into(), ColumnType::BigInt),
+                ColumnRef::new(lowercase_table_ref.clone(), "a".into(), ColumnType::BigInt),
+            ],
+            &accessor,
+        );
+
+        let upper_plan = produce_plan_trustlessly_with_casing::<DynamicDoryEvaluationProof>(
+            &query_parsed,
+            &query_commitments,
+            IdentCasing::Upper,
+        )
+        .unwrap();
+        let preserved_plan = produce_plan_trustlessly_with_casing::<DynamicDoryEvaluationProof>(
+            &query_parsed,
+            &query_commitments,
+            IdentCasing::Preserve,
+        )
+        .unwrap();
+
+        assert_eq!(
+            upper_plan
+                .get_table_references()
+                .into_iter()
+                .collect::<Vec<_>>(),
+            vec![uppercase_table_ref]
+        );
+        assert_eq!(
+            preserved_plan
+                .get_table_references()
+                .into_iter()
+                .collect::<Vec<_>>(),
+            vec![lowercase_table_ref]
+        );
+    }
+
+    #[test]
+    fn we_can_narrow_the_commitments_to_the_referenced_tables() {
+        let sql = r"SELECT a FROM tab WHERE a > 1;";
+        let query_parsed = Parser::parse_sql(&GenericDialect {}, sql).unwrap()[0].clone();
+        let table_ref = TableRef::from_names(None, "TAB");
+        let unused_table_ref = TableRef::from_names(None, "UNUSED");
+        let alloc = Bump::new();
+        let public_parameters = PublicParameters::test_rand(5, &mut test_rng());
+        let prover_setup = ProverSetup::from(&public_parameters);
+        let mut accessor = TableTestAccessor::<DynamicDoryEvaluationProof>::new_from_table(
+            table_ref.clone(),
+            table::<DoryScalar>(vec![borrowed_bigint("A", [1, 2], &alloc)]),
+            0,
+            &prover_setup,
+        );
+        accessor.add_table(
+            unused_table_ref.clone(),
+            table::<DoryScalar>(vec![borrowed_bigint("A", [3, 4], &alloc)]),
+            0,
+        );
+        let query_commitments = QueryCommitments::from_accessor_with_max_bounds(
+            vec![
+                ColumnRef::new(table_ref.clone(), "A".into(), ColumnType::BigInt),
+                ColumnRef::new(unused_table_ref, "A".into(), ColumnType::BigInt),
+            ],
+            &accessor,
+        );
+
+        let referenced =
+            referenced_commitments(&query_parsed, &query_commitments, IdentCasing::Upper).unwrap();
+
+        assert_eq!(referenced.keys().collect::<Vec<_>>(), vec![&table_ref]);
+        produce_dory_plan_trustlessly(&query_parsed, &query_commitments).unwrap();
+    }
+
+    #[test]
+    fn we_cannot_plan_a_query_against_missing_tables() {
+        let sql = r"SELECT tab.a FROM tab JOIN missing ON tab.a = missing.a JOIN sch.gone ON true;";
+        let query_parsed = Parser::parse_sql(&GenericDialect {}, sql).unwrap()[0].clone();
+        let table_ref = TableRef::from_names(None, "TAB");
+        let alloc = Bump::new();
+        let public_parameters = PublicParameters::test_rand(5, &mut test_rng());
+        let prover_setup = ProverSetup::from(&public_parameters);
+        let accessor = TableTestAccessor::<DynamicDoryEvaluationProof>::new_from_table(
+            table_ref.clone(),
+            table::<DoryScalar>(vec![borrowed_bigint("A", [1, 2], &alloc)]),
+            0,
+            &prover_setup,
+        );
+        let query_commitments = QueryCommitments::from_accessor_with_max_bounds(
+            vec![ColumnRef::new(table_ref, "A".into(), ColumnType::BigInt)],
+            &accessor,
+        );
+
+        let err = produce_dory_plan_trustlessly(&query_parsed, &query_commitments).unwrap_err();
+        assert!(matches!(
+            &err,
+            PlanProverQueryError::TableNotFound { tables } if *tables == vec![
+                TableRef::from_names(None, "MISSING"),
+                TableRef::from_names(Some("SCH"), "GONE"),
+            ]
+        ));
+        assert_eq!(err.to_string(), "tables not found: MISSING, SCH.GONE");
+    }
+
+    #[test]
+    fn we_cannot_plan_a_query_against_a_table_name_with_more_than_two_parts() {
+        let sql = r"SELECT a FROM db.sch.tab;";
+        let query_parsed = Parser::parse_sql(&GenericDialect {}, sql).unwrap()[0].clone();
+
+        let err =
+            produce_dory_plan_trustlessly(&query_parsed, &QueryCommitments::default()).unwrap_err();
+
+        assert!(matches!(
+            err,
+            PlanProverQueryError::ColumnRefs {
+                source: ColumnRefError::UnsupportedTableName { .. }
+            }
+        ));
+    }
+
+    fn planning_parse_error(sql: &str) -> PlanProverQueryError {
+        parse_single_statement(sql).unwrap_err().into()
+    }
+
+    #[test]
+    fn we_cannot_plan_an_empty_query() {
+        assert!(matches!(
+            planning_parse_error(""),
+            PlanProverQueryError::EmptyQuery
+        ));
+        assert!(matches!(
+            planning_parse_error("  \n\t "),
+            PlanProverQueryError::EmptyQuery
+        ));
+    }
+
+    #[test]
+    fn we_cannot_plan_multiple_statements() {
+        assert!(matches!(
+            planning_parse_error("SELECT a FROM tab; SELECT b FROM tab"),
+            PlanProverQueryError::MultipleStatements { count: 2 }
+        ));
+    }
+
+    #[test]
+    fn we_report_invalid_sql_as_a_parse_error() {
+        assert!(matches!(
+            planning_parse_error("SELEC a FROM tab"),
+            PlanProverQueryError::Parse { .. }
+        ));
+    }
 }
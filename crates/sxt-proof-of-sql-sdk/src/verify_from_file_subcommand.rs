@@ -0,0 +1,142 @@
+use crate::{
+    base::{
+        verify_from_zk_query_and_substrate_responses,
+        zk_query_models::query_results_response_from_json, CommitmentEvaluationProofId,
+        CommitmentScheme,
+    },
+    native::DynOwnedTable,
+};
+use bumpalo::Bump;
+use clap::Args;
+use datafusion::arrow::{record_batch::RecordBatch, util::pretty::pretty_format_batches};
+#[cfg(feature = "hyperkzg")]
+use proof_of_sql::proof_primitive::hyperkzg::HyperKZGCommitmentEvaluationProof;
+use proof_of_sql::{
+    base::{commitment::CommitmentEvaluationProof, database::OwnedTable},
+    proof_primitive::dory::DynamicDoryEvaluationProof,
+};
+use std::path::PathBuf;
+use subxt::utils::H160;
+
+/// Verify a previously captured ZK Query response without any network access.
+///
+/// The response file is a `QueryResultsResponse` JSON document, which carries the proof plan,
+/// proof, result and attested commitments together.
+#[derive(Args, Debug, Clone, PartialEq, Eq)]
+pub struct VerifyFromFileArgs {
+    /// Path to the captured query results response JSON file
+    #[arg(long, value_name = "RESPONSE")]
+    pub response: PathBuf,
+
+    /// Commitment scheme the response was proven with. Defaults to `HyperKzg`.
+    #[arg(
+        long,
+        value_enum,
+        env,
+        default_value_t = CommitmentScheme::HyperKzg,
+    )]
+    pub commitment_scheme: CommitmentScheme,
+
+    #[arg(
+        long,
+        value_name = "VERIFIER_SETUP",
+        help = "Path to the verifier setup file. If not provided, defaults to the appropriate verifier setup for the selected commitment scheme."
+    )]
+    pub verifier_setup: Option<String>,
+
+    /// Addresses of attestors that must have signed the commitments
+    #[arg(long = "attestor", value_name = "ATTESTOR")]
+    pub attestors: Vec<H160>,
+}
+
+fn verify_from_file_by_cpi<CPI>(
+    args: &VerifyFromFileArgs,
+    bump: &Bump,
+) -> Result<OwnedTable<<CPI as CommitmentEvaluationProof>::Scalar>, Box<dyn core::error::Error>>
+where
+    CPI: CommitmentEvaluationProofId,
+    <CPI as CommitmentEvaluationProofId>::DeserializationError: 'static,
+{
+    let verifier_setup_bytes = match &args.verifier_setup {
+        Some(path) => &std::fs::read(path)?,
+        None => CPI::DEFAULT_VERIFIER_SETUP_BYTES,
+    };
+    let verifier_setup = CPI::deserialize_verifier_setup(verifier_setup_bytes, bump)?;
+
+    let query_results =
+        query_results_response_from_json(&std::fs::read_to_string(&args.response)?)?;
+
+    verify_from_zk_query_and_substrate_responses::<CPI>(
+        query_results,
+        args.attestors.iter().map(|attestor| attestor.0).collect(),
+        &verifier_setup,
+    )
+}
+
+/// Verify the response file described by `args`.
+pub fn verify_from_file(
+    args: &VerifyFromFileArgs,
+) -> Result<DynOwnedTable, Box<dyn core::error::Error>> {
+    let bump = Bump::new();
+    match args.commitment_scheme {
+        CommitmentScheme::DynamicDory => {
+            verify_from_file_by_cpi::<DynamicDoryEvaluationProof>(args, &bump)
+                .map(DynOwnedTable::Dory)
+        }
+        #[cfg(feature = "hyperkzg")]
+        CommitmentScheme::HyperKzg => {
+            verify_from_file_by_cpi::<HyperKZGCommitmentEvaluationProof>(args, &bump)
+                .map(DynOwnedTable::BN)
+        }
+    }
+}
+
+pub fn verify_from_file_command(
+    args: VerifyFromFileArgs,
+) -> Result<(), Box<dyn core::error::Error>> {
+    let result: RecordBatch = verify_from_file(&args)?.try_into()?;
+
+    println!("Query result:\n{}", pretty_format_batches(&[result])?);
+    Ok(())
+}
+
+#[cfg(test)]
+#[cfg(feature = "hyperkzg")]
+mod tests {
+    use super::*;
+    use crate::native::mock_server::tampered_fixture_file;
+
+    const VALID_GATEWAY_RESPONSE_PATH: &str = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/../../test_assets/valid_gateway_response.json"
+    );
+
+    fn args(response: PathBuf) -> VerifyFromFileArgs {
+        VerifyFromFileArgs {
+            response,
+            commitment_scheme: CommitmentScheme::HyperKzg,
+            verifier_setup: None,
+            attestors: vec!["0x349b729d1cEeAAe54fAB5655F621750Be6FadB49"
+                .parse()
+                .unwrap()],
+        }
+    }
+
+    #[test]
+    fn we_can_verify_a_response_from_file() {
+        let result = verify_from_file(&args(VALID_GATEWAY_RESPONSE_PATH.into())).unwrap();
+        let DynOwnedTable::BN(table) = result else {
+            panic!("Expected a HyperKZG table");
+        };
+        assert_eq!(table.num_rows(), 1);
+    }
+
+    #[test]
+    fn we_cannot_verify_a_response_from_file_with_a_tampered_proof() {
+        let path = tampered_fixture_file("proof");
+        let result = verify_from_file(&args(path.clone()));
+        std::fs::remove_file(path).unwrap();
+
+        assert!(result.is_err());
+    }
+}
@@ -1,43 +1,245 @@
 #![doc = include_str!("README.md")]
 
 use crate::base::{
-    verify_from_zk_query_and_substrate_responses, zk_query_models::QueryResultsResponse,
+    verifiable_commitment::commitment_leaf_hash, verify_from_zk_query_and_substrate_responses,
+    zk_query_models::QueryResultsResponse, CommitmentEvaluationProofId, CommitmentScheme,
 };
+use ark_serialize::{CanonicalDeserialize, Compress, Validate};
 use gloo_utils::format::JsValueSerdeExt;
+#[cfg(feature = "hyperkzg")]
 use nova_snark::provider::hyperkzg::VerifierKey;
+#[cfg(feature = "hyperkzg")]
 use proof_of_sql::{
     base::try_standard_binary_deserialization,
     proof_primitive::hyperkzg::{HyperKZGCommitmentEvaluationProof, HyperKZGEngine},
 };
+use proof_of_sql::{
+    base::{database::OwnedTable, scalar::Scalar},
+    proof_primitive::dory::{DoryScalar, DynamicDoryEvaluationProof, VerifierSetup},
+};
+use std::cell::RefCell;
 use wasm_bindgen::prelude::*;
 
 /// Proof-of-sql verifier setup serialized as bytes.
+#[cfg(feature = "hyperkzg")]
 const HYPER_KZG_VERIFIER_SETUP_BYTES: &[u8; 160] =
     include_bytes!("../../verifier_setups/hyper-kzg.bin");
 
+thread_local! {
+    /// Hyper KZG verifier setup set with [`set_verifier_setup`], used instead of the bundled one.
+    #[cfg(feature = "hyperkzg")]
+    static HYPER_KZG_VERIFIER_SETUP_OVERRIDE: RefCell<Option<Vec<u8>>> =
+        const { RefCell::new(None) };
+    /// Dynamic Dory verifier setup set with [`set_verifier_setup`], used instead of the bundled one.
+    static DYNAMIC_DORY_VERIFIER_SETUP_OVERRIDE: RefCell<Option<Vec<u8>>> =
+        const { RefCell::new(None) };
+}
+
+#[cfg(feature = "hyperkzg")]
+fn deserialize_hyper_kzg_verifier_setup(
+    bytes: &[u8],
+) -> Result<VerifierKey<HyperKZGEngine>, String> {
+    try_standard_binary_deserialization(bytes)
+        .map(|(setup, _)| setup)
+        .map_err(|err| err.to_string())
+}
+
+fn deserialize_dynamic_dory_verifier_setup(bytes: &[u8]) -> Result<VerifierSetup, String> {
+    VerifierSetup::deserialize_with_mode(bytes, Compress::No, Validate::No)
+        .map_err(|err| err.to_string())
+}
+
+/// Replace the bundled verifier setup of a commitment scheme, e.g. with one loaded from a CDN.
+///
+/// `scheme` is parsed like in [`commitment_leaf_hex`]. The setup is only replaced if `bytes`
+/// deserialize into a verifier setup for that scheme.
+#[wasm_bindgen]
+pub fn set_verifier_setup(scheme: &str, bytes: &[u8]) -> Result<(), String> {
+    let scheme: CommitmentScheme = scheme.parse().map_err(|e| format!("{e}"))?;
+    match scheme {
+        #[cfg(feature = "hyperkzg")]
+        CommitmentScheme::HyperKzg => {
+            deserialize_hyper_kzg_verifier_setup(bytes)
+                .map_err(|e| format!("invalid verifier setup: {e}"))?;
+            HYPER_KZG_VERIFIER_SETUP_OVERRIDE.set(Some(bytes.to_vec()));
+        }
+        CommitmentScheme::DynamicDory => {
+            deserialize_dynamic_dory_verifier_setup(bytes)
+                .map_err(|e| format!("invalid verifier setup: {e}"))?;
+            DYNAMIC_DORY_VERIFIER_SETUP_OVERRIDE.set(Some(bytes.to_vec()));
+        }
+    }
+    Ok(())
+}
+
+fn deserialize_prover_response(
+    prover_response_json: JsValue,
+) -> Result<QueryResultsResponse, String> {
+    prover_response_json
+        .into_serde()
+        .map_err(|e| format!("failed to deserialize prover response json: {e}"))
+}
+
+fn verified_table_to_json<S: Scalar>(table: OwnedTable<S>) -> Result<JsValue, String> {
+    let verified_table_result: Vec<_> = table
+        .into_inner()
+        .into_iter()
+        .map(|(ident, col)| (ident.to_string(), col))
+        .collect();
+
+    JsValue::from_serde(&verified_table_result)
+        .map_err(|e| format!("failed to convert verified table result to json: {e}"))
+}
+
 /// Verify a response from the prover service against the provided commitment accessor.
+#[cfg(feature = "hyperkzg")]
 #[wasm_bindgen]
 pub fn verify_prover_response_hyper_kzg(prover_response_json: JsValue) -> Result<JsValue, String> {
-    let prover_response: QueryResultsResponse = prover_response_json
-        .into_serde()
-        .map_err(|e| format!("failed to deserialize prover response json: {e}"))?;
+    let prover_response = deserialize_prover_response(prover_response_json)?;
 
-    let verifier_setup: VerifierKey<HyperKZGEngine> =
-        try_standard_binary_deserialization(HYPER_KZG_VERIFIER_SETUP_BYTES)
-            .map(|(setup, _)| setup)
-            .map_err(|err| err.to_string())?;
+    let verifier_setup = HYPER_KZG_VERIFIER_SETUP_OVERRIDE.with_borrow(|setup| {
+        deserialize_hyper_kzg_verifier_setup(
+            setup.as_deref().unwrap_or(HYPER_KZG_VERIFIER_SETUP_BYTES),
+        )
+    })?;
 
-    let verified_table_result: Vec<_> = verify_from_zk_query_and_substrate_responses::<
+    let verified_table = verify_from_zk_query_and_substrate_responses::<
         HyperKZGCommitmentEvaluationProof,
     >(prover_response, Vec::new(), &&verifier_setup)
-    .map_err(|e| format!("verification failure: {e}"))?
-    .into_inner()
-    .into_iter()
-    .map(|(ident, col)| (ident.to_string(), col))
-    .collect();
+    .map_err(|e| format!("verification failure: {e}"))?;
+
+    verified_table_to_json(verified_table)
+}
+
+/// Verify a Dynamic Dory response from the prover service against its attested commitments.
+#[wasm_bindgen]
+pub fn verify_prover_response_dory(prover_response_json: JsValue) -> Result<JsValue, String> {
+    let prover_response = deserialize_prover_response(prover_response_json)?;
+    verified_table_to_json(verify_dory_prover_response(prover_response)?)
+}
+
+fn verify_dory_prover_response(
+    prover_response: QueryResultsResponse,
+) -> Result<OwnedTable<DoryScalar>, String> {
+    let verifier_setup = DYNAMIC_DORY_VERIFIER_SETUP_OVERRIDE.with_borrow(|setup| {
+        deserialize_dynamic_dory_verifier_setup(
+            setup
+                .as_deref()
+                .unwrap_or(DynamicDoryEvaluationProof::DEFAULT_VERIFIER_SETUP_BYTES),
+        )
+    })?;
+
+    verify_from_zk_query_and_substrate_responses::<DynamicDoryEvaluationProof>(
+        prover_response,
+        Vec::new(),
+        &&verifier_setup,
+    )
+    .map_err(|e| format!("verification failure: {e}"))
+}
+
+/// Compute the hash of the attestation Merkle leaf for a table commitment.
+///
+/// `commitment_hex` is the serialized table commitment, with or without a `0x` prefix.
+#[wasm_bindgen]
+pub fn commitment_leaf_hex(
+    table_id: &str,
+    scheme: &str,
+    commitment_hex: &str,
+) -> Result<String, String> {
+    let scheme: CommitmentScheme = scheme.parse().map_err(|e| format!("{e}"))?;
+    let commitment = hex::decode(commitment_hex.strip_prefix("0x").unwrap_or(commitment_hex))
+        .map_err(|e| format!("failed to decode commitment hex: {e}"))?;
+    commitment_leaf_hash(table_id.to_string(), scheme, commitment)
+        .map_err(|e| format!("failed to hash commitment leaf: {e:?}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::base::{prover, zk_query_models::query_results_response_from_json};
+
+    fn fixture_response() -> QueryResultsResponse {
+        query_results_response_from_json(include_str!(
+            "../../../../test_assets/valid_gateway_response.json"
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn we_can_set_a_valid_verifier_setup() {
+        set_verifier_setup(
+            "DynamicDory",
+            DynamicDoryEvaluationProof::DEFAULT_VERIFIER_SETUP_BYTES,
+        )
+        .unwrap();
+        assert_eq!(
+            DYNAMIC_DORY_VERIFIER_SETUP_OVERRIDE.with_borrow(Clone::clone),
+            Some(DynamicDoryEvaluationProof::DEFAULT_VERIFIER_SETUP_BYTES.to_vec())
+        );
+
+        #[cfg(feature = "hyperkzg")]
+        {
+            set_verifier_setup("HYPER_KZG", HYPER_KZG_VERIFIER_SETUP_BYTES).unwrap();
+            assert_eq!(
+                HYPER_KZG_VERIFIER_SETUP_OVERRIDE.with_borrow(Clone::clone),
+                Some(HYPER_KZG_VERIFIER_SETUP_BYTES.to_vec())
+            );
+        }
+    }
+
+    #[test]
+    fn we_cannot_set_an_invalid_verifier_setup() {
+        let truncated = &DynamicDoryEvaluationProof::DEFAULT_VERIFIER_SETUP_BYTES[..16];
+        assert!(set_verifier_setup("DynamicDory", truncated).is_err());
+        assert_eq!(
+            DYNAMIC_DORY_VERIFIER_SETUP_OVERRIDE.with_borrow(Clone::clone),
+            None
+        );
+
+        #[cfg(feature = "hyperkzg")]
+        assert!(set_verifier_setup("HyperKzg", &HYPER_KZG_VERIFIER_SETUP_BYTES[..16]).is_err());
+
+        assert!(set_verifier_setup("ipa", &[]).is_err());
+    }
+
+    // There is no Dory happy-path test here. A verifiable Dory response needs a locally planned
+    // and proven query plus commitments attested by signers, and neither the planner nor the
+    // proof-of-sql prover test utilities are available under the `wasm-dory` feature alone. The
+    // Dory proof verification underneath is covered by `base::verify::dory_tests`.
+    #[test]
+    fn we_cannot_verify_a_response_for_another_scheme_as_dory() {
+        // The fixture was proven with HyperKZG
+        let error = verify_dory_prover_response(fixture_response()).unwrap_err();
+        assert!(
+            error.starts_with("verification failure: expected a response for commitment scheme")
+        );
+    }
+
+    #[test]
+    fn we_cannot_verify_a_malformed_dory_response() {
+        let mut response = fixture_response();
+        response.commitment_scheme = prover::CommitmentScheme::DynamicDory;
+        response.proof.truncate(8);
+        let error = verify_dory_prover_response(response).unwrap_err();
+        assert!(error.starts_with("verification failure: "));
+    }
+
+    #[cfg(feature = "hyperkzg")]
+    #[test]
+    fn we_can_compute_the_commitment_leaf_hex_of_a_fixture_commitment() {
+        let response = fixture_response();
+        let commitment_hex = format!(
+            "0x{}",
+            hex::encode(&response.commitments.commitments["ETHEREUM.BLOCKS"].commitment)
+        );
 
-    let verified_table_result_json = JsValue::from_serde(&verified_table_result)
-        .map_err(|e| format!("failed to convert verified table result to json: {e}"))?;
+        let leaf_hex = commitment_leaf_hex("ETHEREUM.BLOCKS", "HyperKzg", &commitment_hex).unwrap();
 
-    Ok(verified_table_result_json)
+        assert_eq!(
+            hex::decode(leaf_hex.trim_start_matches("0x")).unwrap(),
+            hex::decode("2c9cf07166712e8bb5ad97caea0ff723a6d084d94870193a4e9b8862d6d603fc")
+                .unwrap()
+        );
+        assert!(commitment_leaf_hex("ETHEREUM.BLOCKS", "HyperKzg", "0xzz").is_err());
+    }
 }
@@ -2,7 +2,7 @@ import { z } from 'zod'
 
 const numberColumnSchema = z.array(z.number())
 const bigintColumnSchema = z.array(z.string().transform((val) => BigInt(val)))
-const errorMessageVariants = z.enum(["QueryResultsDeserialization", "AttestorDeserialization", "VerificationError", "TypeConversion", "Serialization"])
+const errorMessageVariants = z.enum(["QueryResultsDeserialization", "AttestorDeserialization", "VerificationError", "TypeConversion", "Serialization", "QueryError"])
 const timeUnitSchema = z.union([
     z.literal('Second'),
     z.literal('Millisecond'),
@@ -31,17 +31,44 @@ const columnSchema = z.discriminatedUnion('type', [
     z.object({
         type: z.literal('VarBinary'),
         column: z.array(
-            z.array(
-                z
-                    .number()
-                    .min(0)
-                    .max(255)
-                    .transform((byteArray) => new Uint8Array(byteArray)),
-            ),
+            z.union([
+                z.array(
+                    z
+                        .number()
+                        .min(0)
+                        .max(255)
+                        .transform((byteArray) => new Uint8Array(byteArray)),
+                ),
+                // Only emitted for Rust callers that set `JsonResultOptions::varbinary_as_hex`;
+                // the `verify` function exported to JavaScript always emits byte arrays.
+                z.string().regex(/^0x([0-9a-fA-F]{2})*$/),
+            ]),
         ),
     }),
     z.object({ type: z.literal('Scalar'), column: bigintColumnSchema }),
 ])
+const columnTypeSchema = z.discriminatedUnion('type', [
+    z.object({ name: z.string(), type: z.literal('Boolean') }),
+    z.object({ name: z.string(), type: z.literal('TinyInt') }),
+    z.object({ name: z.string(), type: z.literal('SmallInt') }),
+    z.object({ name: z.string(), type: z.literal('Int') }),
+    z.object({ name: z.string(), type: z.literal('BigInt') }),
+    z.object({ name: z.string(), type: z.literal('VarChar') }),
+    z.object({
+        name: z.string(),
+        type: z.literal('Decimal75'),
+        precision: z.number(),
+        scale: z.number(),
+    }),
+    z.object({
+        name: z.string(),
+        type: z.literal('TimestampTZ'),
+        timeUnit: timeUnitSchema,
+        offset: z.number(),
+    }),
+    z.object({ name: z.string(), type: z.literal('VarBinary') }),
+    z.object({ name: z.string(), type: z.literal('Scalar') }),
+])
 export const proofOfSqlResultSchema = z.discriminatedUnion('verificationStatus', [
     z.object({
         verificationStatus: z.literal('Success'),
@@ -49,6 +76,7 @@ export const proofOfSqlResultSchema = z.discriminatedUnion('verificationStatus',
             z.string(),
             columnSchema,
         ),
+        schema: z.array(columnTypeSchema),
     }),
     z.object({ verificationStatus: z.literal('Failure'), error: errorMessageVariants, message: z.string() }),
 ])
\ No newline at end of file